@@ -0,0 +1,92 @@
+//! Checking that a converted URL exists before opening it (the `check` feature).
+
+use std::io::Write;
+use std::time::Duration;
+
+use crate::Result;
+
+/// Makes a HEAD request to `url` and writes its status to `out`, with a
+/// warning if it isn't a success or redirect. Returns the status code, or
+/// `None` if `url` couldn't be reached at all, which only gets a warning since
+/// it says nothing about the URL being wrong (e.g. when offline).
+pub fn check_url<W: Write>(url: &str, out: &mut W) -> Result<Option<u16>> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build();
+    let status = match agent.head(url).call() {
+        Ok(response) => response.status(),
+        Err(ureq::Error::Status(status, _)) => status,
+        Err(e) => {
+            writeln!(out, "Warning: couldn't reach {} to check it: {}", url, e)?;
+            return Ok(None);
+        }
+    };
+    writeln!(out, "HEAD {}: {}", url, status)?;
+    if !(200..400).contains(&status) {
+        writeln!(
+            out,
+            "Warning: {} returned {}, the remote may have been converted to the wrong URL",
+            url, status
+        )?;
+    }
+    Ok(Some(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves a single request with `status` and an empty body.
+    fn serve_once(status: &'static str) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+        });
+        Ok(format!("http://{}/n8henrie/git-repo", addr))
+    }
+
+    #[test]
+    fn test_check_url_not_found() -> Result<()> {
+        let url = serve_once("404 Not Found")?;
+        let mut out = Vec::new();
+        assert_eq!(check_url(&url, &mut out)?, Some(404));
+        let out = String::from_utf8_lossy(&out);
+        assert!(out.contains(": 404"));
+        assert!(out.contains("Warning"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_url_ok() -> Result<()> {
+        let url = serve_once("200 OK")?;
+        let mut out = Vec::new();
+        assert_eq!(check_url(&url, &mut out)?, Some(200));
+        assert!(!String::from_utf8_lossy(&out).contains("Warning"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_url_unreachable() -> Result<()> {
+        let addr = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        let url = format!("http://{}/n8henrie/git-repo", addr);
+        let mut out = Vec::new();
+        assert_eq!(check_url(&url, &mut out)?, None);
+        assert!(String::from_utf8_lossy(&out).contains("Warning: couldn't reach"));
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//! The optional config file with defaults for command line flags.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{Action, HostMap, Result, DEFAULT_OPEN_COUNT};
+
+/// Defaults read from `$XDG_CONFIG_HOME/git-repo/config.toml`. Every key is
+/// optional, and unknown keys are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// What to do with the URL when no action flag is given.
+    pub default_action: Option<Action>,
+    /// Whether to prompt for the action when no action flag is given.
+    pub interactive_action: Option<bool>,
+    /// Whether to read remote URLs with `git remote get-url`, like `--get-url`.
+    pub get_url: Option<bool>,
+    /// Whether to expand `Host` aliases from `~/.ssh/config`, like `--ssh-config`.
+    pub ssh_config: Option<bool>,
+    /// The remote to use when `--remote` isn't given.
+    pub default_remote: Option<String>,
+    /// How many URLs `--all` or several FILEs open before asking first, like
+    /// `--open-count`.
+    pub open_count: Option<usize>,
+    /// Whether to strip the trailing `.git` from URLs.
+    pub strip_git: Option<bool>,
+    /// Providers for self-hosted instances, from a `[hosts]` table such as
+    /// `"github.mycorp.com" = "github"`.
+    pub hosts: HostMap,
+}
+
+/// The config file location given the values of `$XDG_CONFIG_HOME` and
+/// `$HOME`, or `None` if neither is usable.
+pub fn config_path(xdg_config_home: Option<OsString>, home: Option<OsString>) -> Option<PathBuf> {
+    let config_home = xdg_config_home
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            home.filter(|home| !home.is_empty())
+                .map(|home| Path::new(&home).join(".config"))
+        })?;
+    Some(config_home.join("git-repo").join("config.toml"))
+}
+
+impl Config {
+    /// Loads the config file from [`config_path`], or the defaults if there is
+    /// no config file.
+    pub fn load() -> Result<Self> {
+        match config_path(
+            std::env::var_os("XDG_CONFIG_HOME"),
+            std::env::var_os("HOME"),
+        ) {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the config file at `path`, or the defaults if it doesn't exist.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Parses the contents of a config file.
+    pub fn parse(contents: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// The action to take: the one chosen on the command line (or with
+    /// `$GIT_REPO_ACTION`), if any, then the configured one, then
+    /// [`Action::Open`].
+    pub fn action(&self, cli: Option<Action>) -> Action {
+        cli.or(self.default_action).unwrap_or_default()
+    }
+
+    /// Whether to prompt for the action: `cli` is set by `--interactive-action`,
+    /// otherwise the configured value is used, defaulting to `false`.
+    pub fn interactive_action(&self, cli: bool) -> bool {
+        cli || self.interactive_action.unwrap_or(false)
+    }
+
+    /// Whether to read remote URLs with `git remote get-url`: `cli` is set by
+    /// `--get-url`, otherwise the configured value is used, defaulting to `false`.
+    pub fn get_url(&self, cli: bool) -> bool {
+        cli || self.get_url.unwrap_or(false)
+    }
+
+    /// Whether to expand ssh `Host` aliases: `cli` is set by `--ssh-config`,
+    /// otherwise the configured value is used, defaulting to `false`.
+    pub fn ssh_config(&self, cli: bool) -> bool {
+        cli || self.ssh_config.unwrap_or(false)
+    }
+
+    /// The remote to use: the one named on the command line, if any, then the
+    /// configured one.
+    pub fn remote<'a>(&'a self, cli: Option<&'a str>) -> Option<&'a str> {
+        cli.or(self.default_remote.as_deref())
+    }
+
+    /// How many URLs `--all` opens before asking first: the number given to
+    /// `--open-count`, if any, then the configured one, then
+    /// [`DEFAULT_OPEN_COUNT`].
+    pub fn open_count(&self, cli: Option<usize>) -> usize {
+        cli.or(self.open_count).unwrap_or(DEFAULT_OPEN_COUNT)
+    }
+
+    /// Whether to strip `.git`: `cli` is `Some` when a flag chose explicitly,
+    /// otherwise the configured value is used, defaulting to `true`.
+    pub fn strip_git(&self, cli: Option<bool>) -> bool {
+        cli.or(self.strip_git).unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_path() {
+        assert_eq!(
+            config_path(Some("/xdg".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/xdg/git-repo/config.toml"))
+        );
+        assert_eq!(
+            config_path(Some("relative".into()), Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.config/git-repo/config.toml"))
+        );
+        assert_eq!(
+            config_path(None, Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.config/git-repo/config.toml"))
+        );
+        assert_eq!(config_path(None, None), None);
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            default_action = "print"
+            interactive_action = true
+            get_url = true
+            ssh_config = true
+            default_remote = "upstream"
+            strip_git = false
+            open_count = 3
+            some_future_key = 42
+            "#,
+        )?;
+        assert_eq!(
+            config,
+            Config {
+                default_action: Some(Action::Print),
+                interactive_action: Some(true),
+                get_url: Some(true),
+                ssh_config: Some(true),
+                default_remote: Some("upstream".into()),
+                strip_git: Some(false),
+                open_count: Some(3),
+                hosts: HostMap::default(),
+            }
+        );
+        assert_eq!(Config::parse("")?, Config::default());
+        assert!(Config::parse(r#"default_action = "shout""#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_hosts() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [hosts]
+            "github.mycorp.com" = "github"
+            "git.mycorp.com" = "gitlab"
+            "#,
+        )?;
+        assert_eq!(
+            config.hosts,
+            HostMap::parse("github.mycorp.com=github,git.mycorp.com=gitlab")?
+        );
+        assert!(Config::parse("[hosts]\n\"git.mycorp.com\" = \"sourcehut\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_missing() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(
+            Config::load_from(&dir.path().join("config.toml"))?,
+            Config::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cli_overrides_config() {
+        let defaults = Config::default();
+        assert_eq!(defaults.action(None), Action::Open);
+        assert_eq!(defaults.remote(None), None);
+        assert!(defaults.strip_git(None));
+        assert!(!defaults.interactive_action(false));
+        assert!(defaults.interactive_action(true));
+        assert!(!defaults.get_url(false));
+
+        let config = Config {
+            default_action: Some(Action::Copy),
+            default_remote: Some("upstream".into()),
+            strip_git: Some(false),
+            ..Config::default()
+        };
+        assert_eq!(config.action(None), Action::Copy);
+        assert_eq!(config.action(Some(Action::Print)), Action::Print);
+        assert_eq!(config.remote(None), Some("upstream"));
+        assert_eq!(config.remote(Some("origin")), Some("origin"));
+        assert!(!config.strip_git(None));
+        assert!(config.strip_git(Some(true)));
+    }
+}
@@ -0,0 +1,192 @@
+//! Answers to the questions asked of git during a run, each asked at most once.
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    commit_sha_with, current_branch_with, default_branch_with, git_output_with,
+    remotes_from_get_url_with, repo_root_with, upstream_branch_with, verify_ref_with,
+    verify_tag_with, CommandRunner, Remote, Result,
+};
+
+/// Runs git in one directory with `runner`, remembering each answer so that
+/// asking again doesn't run git again. Failures aren't remembered.
+pub struct GitContext<'a> {
+    runner: &'a dyn CommandRunner,
+    dir: Option<&'a Path>,
+    remotes: OnceCell<String>,
+    toplevel: OnceCell<PathBuf>,
+    current_branch: OnceCell<Option<String>>,
+    head_sha: OnceCell<String>,
+    upstream_branches: RefCell<HashMap<String, Option<String>>>,
+    default_branches: RefCell<HashMap<String, String>>,
+    default_branch_override: Option<String>,
+}
+
+/// The value in `cell`, computing and storing it with `init` if it's empty.
+fn cached<T: Clone>(cell: &OnceCell<T>, init: impl FnOnce() -> Result<T>) -> Result<T> {
+    if let Some(value) = cell.get() {
+        return Ok(value.clone());
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value).clone())
+}
+
+impl<'a> GitContext<'a> {
+    /// Runs git in `dir`, or the current directory, with `runner`.
+    pub fn new(runner: &'a dyn CommandRunner, dir: Option<&'a Path>) -> Self {
+        GitContext {
+            runner,
+            dir,
+            remotes: OnceCell::new(),
+            toplevel: OnceCell::new(),
+            current_branch: OnceCell::new(),
+            head_sha: OnceCell::new(),
+            upstream_branches: RefCell::default(),
+            default_branches: RefCell::default(),
+            default_branch_override: None,
+        }
+    }
+
+    /// Uses `branch`, if given, as the default branch of every remote instead
+    /// of asking git.
+    pub fn with_default_branch(mut self, branch: Option<String>) -> Self {
+        self.default_branch_override = branch;
+        self
+    }
+
+    /// The directory git runs in, `None` for the current one.
+    pub fn dir(&self) -> Option<&'a Path> {
+        self.dir
+    }
+
+    /// The output of `git remote --verbose`, as from [`crate::git_output`].
+    pub fn remote_output(&self) -> Result<String> {
+        cached(&self.remotes, || git_output_with(self.runner, self.dir))
+    }
+
+    /// The remotes with their URLs from `git remote get-url`, as from
+    /// [`crate::remotes_from_get_url`].
+    pub fn remotes_from_get_url(&self) -> Result<Vec<Remote>> {
+        remotes_from_get_url_with(self.runner, self.dir)
+    }
+
+    /// The top level of the working tree, as from [`crate::repo_root`].
+    pub fn toplevel(&self) -> Result<PathBuf> {
+        cached(&self.toplevel, || repo_root_with(self.runner, self.dir))
+    }
+
+    /// The checked-out branch, as from [`crate::current_branch`].
+    pub fn current_branch(&self) -> Result<Option<String>> {
+        cached(&self.current_branch, || {
+            current_branch_with(self.runner, self.dir)
+        })
+    }
+
+    /// The branch to show for what's checked out, as from
+    /// [`crate::pushed_branch`].
+    pub fn pushed_branch(&self, remote: &str) -> Result<Option<String>> {
+        let local = match self.current_branch()? {
+            Some(local) => local,
+            None => return Ok(None),
+        };
+        let upstream = self
+            .upstream_branches
+            .borrow_mut()
+            .entry(remote.to_owned())
+            .or_insert_with(|| upstream_branch_with(self.runner, self.dir, remote))
+            .clone();
+        Ok(Some(upstream.unwrap_or(local)))
+    }
+
+    /// The default branch of `remote`, as from [`crate::default_branch_with`], or
+    /// the one given to [`GitContext::with_default_branch`].
+    pub fn default_branch(&self, remote: &str) -> String {
+        if let Some(branch) = &self.default_branch_override {
+            return branch.clone();
+        }
+        self.default_branches
+            .borrow_mut()
+            .entry(remote.to_owned())
+            .or_insert_with(|| default_branch_with(self.runner, self.dir, remote))
+            .clone()
+    }
+
+    /// The full SHA of `HEAD`.
+    pub fn head_sha(&self) -> Result<String> {
+        cached(&self.head_sha, || {
+            commit_sha_with(self.runner, self.dir, "HEAD")
+        })
+    }
+
+    /// The full SHA of the commit `rev` resolves to, as from
+    /// [`crate::commit_sha`]. Only `HEAD` is remembered.
+    pub fn commit_sha(&self, rev: &str) -> Result<String> {
+        match rev {
+            "HEAD" => self.head_sha(),
+            _ => commit_sha_with(self.runner, self.dir, rev),
+        }
+    }
+
+    /// Checks that the tag `tag` exists, as with [`crate::verify_tag`].
+    pub fn verify_tag(&self, tag: &str) -> Result<()> {
+        verify_tag_with(self.runner, self.dir, tag)
+    }
+
+    /// Checks that `rev` names a commit, as it is or as a branch of `remote`,
+    /// as with [`crate::verify_ref`].
+    pub fn verify_ref(&self, remote: &str, rev: &str) -> Result<()> {
+        verify_ref_with(self.runner, self.dir, remote, rev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::mock::MockRunner;
+
+    #[test]
+    fn test_git_context_memoizes() -> Result<()> {
+        let runner = MockRunner::new("origin/main\n");
+        let git = GitContext::new(&runner, None);
+
+        assert_eq!(git.current_branch()?.as_deref(), Some("origin/main"));
+        assert_eq!(git.current_branch()?.as_deref(), Some("origin/main"));
+        assert_eq!(runner.run_count(), 1);
+
+        git.toplevel()?;
+        git.toplevel()?;
+        assert_eq!(runner.run_count(), 2);
+
+        git.head_sha()?;
+        git.commit_sha("HEAD")?;
+        assert_eq!(runner.run_count(), 3);
+
+        git.pushed_branch("origin")?;
+        git.pushed_branch("origin")?;
+        assert_eq!(runner.run_count(), 4);
+
+        git.default_branch("origin");
+        git.default_branch("origin");
+        assert_eq!(runner.run_count(), 5);
+
+        git.commit_sha("v1.0")?;
+        git.commit_sha("v1.0")?;
+        assert_eq!(runner.run_count(), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_branch_override() {
+        let runner = MockRunner::new("origin/main\n");
+        let git = GitContext::new(&runner, None).with_default_branch(Some("trunk".into()));
+        assert_eq!(git.default_branch("origin"), "trunk");
+        assert_eq!(git.default_branch("upstream"), "trunk");
+        assert_eq!(runner.run_count(), 0);
+
+        let git = GitContext::new(&runner, None).with_default_branch(None);
+        assert_eq!(git.default_branch("origin"), "main");
+        assert_eq!(runner.run_count(), 1);
+    }
+}
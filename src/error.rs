@@ -0,0 +1,246 @@
+//! The crate's error type.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+
+/// The ways finding or opening a repo's URL can fail.
+pub enum Error {
+    /// No remote has a URL.
+    NoRemotes,
+    /// git listed no remotes but printed this warning, which likely explains
+    /// why.
+    GitWarning(String),
+    /// No remote has this name or starts with it.
+    NoSuchRemote(String),
+    /// Several remotes start with `prefix`.
+    AmbiguousRemote {
+        prefix: String,
+        candidates: Vec<String>,
+    },
+    /// Several remotes could be used but there's no terminal to prompt on.
+    /// Holds the choices as they would be shown.
+    NeedsSelection(Vec<String>),
+    /// The prompt was closed without picking anything.
+    NoSelection,
+    /// This many invalid selections were entered in a row at the prompt.
+    TooManyAttempts(usize),
+    /// The git executable doesn't exist.
+    GitNotFound(OsString),
+    /// A git command exited with an error.
+    Git {
+        args: String,
+        stderr: String,
+    },
+    /// The browser command exited unsuccessfully, with `code` unless it was
+    /// killed by a signal.
+    BrowserFailed {
+        program: String,
+        code: Option<i32>,
+    },
+    /// Opening URLs or the clipboard isn't supported on this OS.
+    UnsupportedOs(String),
+    /// A remote URL without a repository path, such as `git@github.com:`.
+    MalformedUrl(String),
+    Io(io::Error),
+    /// Any other failure, described by its message.
+    Other(String),
+}
+
+impl Error {
+    /// The status the binary exits with for this error:
+    ///
+    /// - 2 when there are no remotes with URLs
+    /// - 3 when a remote has to be chosen but can't be, because the name is
+    ///   ambiguous or there's no terminal to prompt on
+    /// - 4 when the OS isn't supported
+    /// - 5 when git is missing or fails
+    /// - 1 for anything else
+    ///
+    /// Success is 0. These codes are stable.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::NoRemotes | Error::GitWarning(_) => 2,
+            Error::AmbiguousRemote { .. } | Error::NeedsSelection(_) => 3,
+            Error::UnsupportedOs(_) => 4,
+            Error::GitNotFound(_) | Error::Git { .. } => 5,
+            Error::NoSuchRemote(_)
+            | Error::NoSelection
+            | Error::TooManyAttempts(_)
+            | Error::BrowserFailed { .. }
+            | Error::MalformedUrl(_)
+            | Error::Io(_)
+            | Error::Other(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoRemotes => f.write_str("No URL found"),
+            Error::GitWarning(stderr) => write!(f, "No URL found, git warned: {}", stderr),
+            Error::NoSuchRemote(name) => write!(f, "No remote named {:?}", name),
+            Error::AmbiguousRemote { prefix, candidates } => write!(
+                f,
+                "Several remotes start with {:?}: {}",
+                prefix,
+                candidates.join(", ")
+            ),
+            Error::NeedsSelection(choices) => {
+                f.write_str(
+                    "Found several remotes but can't prompt without a terminal, choose one by name:",
+                )?;
+                choices
+                    .iter()
+                    .try_for_each(|choice| write!(f, "\n  {}", choice))
+            }
+            Error::NoSelection => f.write_str("No selection made"),
+            Error::TooManyAttempts(attempts) => {
+                write!(f, "Too many invalid selections ({})", attempts)
+            }
+            Error::GitNotFound(bin) => write!(
+                f,
+                "git executable `{}` not found; is git installed and on your PATH?",
+                bin.to_string_lossy()
+            ),
+            Error::Git { args, stderr } if stderr.contains("not a git repository") => write!(
+                f,
+                "`git {}` failed, is this a git repository? {}",
+                args, stderr
+            ),
+            Error::Git { args, stderr } => write!(f, "`git {}` failed: {}", args, stderr),
+            Error::BrowserFailed {
+                program,
+                code: Some(code),
+            } => write!(f, "`{}` failed with exit code {}", program, code),
+            Error::BrowserFailed {
+                program,
+                code: None,
+            } => write!(f, "`{}` was killed by a signal", program),
+            Error::UnsupportedOs(os) => write!(
+                f,
+                "so far this only works on Mac, Linux, or Windows, not {}",
+                os
+            ),
+            Error::MalformedUrl(url) => {
+                write!(f, "Remote URL {:?} has no repository path", url)
+            }
+            Error::Io(e) => e.fmt(f),
+            Error::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Shows the message rather than the variant, so that an error returned from a
+/// test or `main` prints something meant for people.
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<String> for Error {
+    fn from(msg: String) -> Self {
+        Error::Other(msg)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(msg: &str) -> Self {
+        Error::Other(msg.to_owned())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Other(e.to_string())
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Other(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        for (err, code) in [
+            (Error::NoRemotes, 2),
+            (
+                Error::GitWarning("fatal: detected dubious ownership".into()),
+                2,
+            ),
+            (
+                Error::AmbiguousRemote {
+                    prefix: "o".into(),
+                    candidates: vec!["origin".into(), "other".into()],
+                },
+                3,
+            ),
+            (Error::NeedsSelection(vec!["0: origin".into()]), 3),
+            (Error::UnsupportedOs("plan9".into()), 4),
+            (Error::GitNotFound("git".into()), 5),
+            (
+                Error::Git {
+                    args: "remote --verbose".into(),
+                    stderr: "fatal: not a git repository".into(),
+                },
+                5,
+            ),
+            (Error::NoSuchRemote("upstream".into()), 1),
+            (Error::NoSelection, 1),
+            (Error::TooManyAttempts(5), 1),
+            (
+                Error::BrowserFailed {
+                    program: "firefox".into(),
+                    code: Some(1),
+                },
+                1,
+            ),
+            (Error::MalformedUrl("git@github.com:".into()), 1),
+            (io::Error::from(io::ErrorKind::NotFound).into(), 1),
+            ("something else".into(), 1),
+        ] {
+            assert_eq!(err.exit_code(), code, "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_git_display() {
+        let err = Error::Git {
+            args: "remote --verbose".into(),
+            stderr: "fatal: not a git repository (or any of the parent directories): .git".into(),
+        };
+        assert!(err.to_string().contains("is this a git repository?"));
+
+        let err = Error::Git {
+            args: "rev-parse HEAD".into(),
+            stderr: "fatal: ambiguous argument 'HEAD': unknown revision".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "`git rev-parse HEAD` failed: fatal: ambiguous argument 'HEAD': unknown revision"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Diagnostic logging to stderr, filtered by `$GIT_REPO_LOG`.
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// The level logged at when `$GIT_REPO_LOG` isn't set.
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Warn;
+
+/// Writes each record to stderr as `[LEVEL target] message`.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The level named by `filter`, such as `debug` (in any case), or
+/// [`DEFAULT_LEVEL`] if it's missing or not a level.
+pub fn parse_level(filter: Option<&str>) -> LevelFilter {
+    filter
+        .and_then(|filter| filter.trim().parse().ok())
+        .unwrap_or(DEFAULT_LEVEL)
+}
+
+/// Logs to stderr at the level named by `filter`, the value of
+/// `$GIT_REPO_LOG`, and returns that level. Calling it again only changes the
+/// level.
+pub fn init(filter: Option<&str>) -> LevelFilter {
+    static LOGGER: StderrLogger = StderrLogger;
+    let level = parse_level(filter);
+    // Fails only if a logger is already set, which is fine.
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level(Some("debug")), LevelFilter::Debug);
+        assert_eq!(parse_level(Some("TRACE")), LevelFilter::Trace);
+        assert_eq!(parse_level(Some("off")), LevelFilter::Off);
+        assert_eq!(parse_level(Some("loud")), DEFAULT_LEVEL);
+        assert_eq!(parse_level(None), DEFAULT_LEVEL);
+    }
+
+    #[test]
+    fn test_init() {
+        assert_eq!(init(Some("debug")), LevelFilter::Debug);
+        assert_eq!(log::max_level(), LevelFilter::Debug);
+        log::debug!("logging works");
+        assert_eq!(init(None), DEFAULT_LEVEL);
+        assert_eq!(log::max_level(), DEFAULT_LEVEL);
+    }
+}
@@ -1,142 +1,1123 @@
-use std::collections::HashSet;
 use std::env::{self, consts::OS};
-use std::io::{self, Write};
-use std::process::{Command, ExitStatus};
-
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
-
-fn select_from_list<T, U>(choices: &mut T) -> Result<U>
-where
-    T: std::iter::Iterator<Item = U>,
-    U: AsRef<str>,
-{
-    for (idx, choice) in choices.by_ref().enumerate() {
-        println!("{}: {}", idx, choice.as_ref());
-    }
-    let mut input = String::new();
-    loop {
-        print!("Choose a number from above: ");
-        io::stdout().flush()?;
-        io::stdin().read_line(&mut input)?;
-        match input.trim().parse::<usize>() {
-            Ok(num) => {
-                if let Some(choice) = choices.by_ref().nth(num) {
-                    return Ok(choice);
-                }
-            }
+use std::ffi::OsString;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use git_repo::{
+    build_branch_url, choose_remote_from, command_line, confirm_from_reader, confirm_open_count,
+    confirm_open_url, copy_to_clipboard, editor_url, format_url, gist_url, https_clone_url,
+    listing_table, mirror_commands, named_web_urls, offer_print_fallback, open_command_with,
+    open_url_with, page_url, raw_url, remote_choices, remote_listing, render_template,
+    repo_relative_path, retain_web_remotes, select_from, split_lines, split_range, ssh_url,
+    url_host, urls_from_output, Action, CommandRunner, Config, Error, GitContext, HostMap, Launch,
+    Lines, Provider, Remote, RemoteSelector, Result, Scheme, SshConfig, SystemRunner,
+    DEFAULT_MAX_ATTEMPTS, DEFAULT_REMOTES,
+};
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about,
+    group(ArgGroup::new("action").multiple(false)),
+    group(ArgGroup::new("page").multiple(false))
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Open the URL in a browser (the default)
+    #[arg(short, long, group = "action")]
+    open: bool,
+
+    /// Print the URL to stdout instead of opening it in a browser
+    #[arg(short, long, visible_alias = "no-open", group = "action")]
+    print: bool,
+
+    /// Print the remote's name and the URL separated by a tab; implies --print
+    #[arg(long, conflicts_with_all = ["open", "copy", "interactive_action", "url"])]
+    print_name: bool,
+
+    /// Copy the URL to the clipboard instead of opening it in a browser
+    #[arg(short, long, group = "action")]
+    copy: bool,
+
+    /// Print the command that would open the URL in a browser instead of
+    /// running it
+    #[arg(long, group = "action", conflicts_with = "print_name")]
+    show_command: bool,
+
+    /// Show the URL and ask before opening it, or refuse without a terminal
+    #[arg(long, conflicts_with_all = ["print", "print_name", "copy", "show_command"])]
+    confirm: bool,
+
+    /// Prompt for whether to open, print, or copy the URL
+    #[arg(long, conflicts_with = "action")]
+    interactive_action: bool,
+
+    /// Use the URL of the remote with this name (or unique prefix) instead of prompting
+    #[arg(short, long, value_name = "NAME")]
+    remote: Option<String>,
+
+    /// Prompt for a remote even if `origin` or `upstream` exists
+    #[arg(long, conflicts_with = "remote")]
+    no_default: bool,
+
+    /// Open the page for BRANCH, defaulting to the one the current branch pushes to
+    #[arg(short, long, value_name = "BRANCH", conflicts_with = "page")]
+    branch: Option<Option<String>>,
+
+    /// Open the pull (or merge) requests page
+    #[arg(long, group = "page")]
+    pulls: bool,
+
+    /// Open the page for the commit REV, defaulting to HEAD
+    #[arg(long, value_name = "REV", group = "page")]
+    commit: Option<Option<String>>,
+
+    /// Open the page for the tag TAG (a release on GitHub)
+    #[arg(long, value_name = "TAG", group = "page")]
+    tag: Option<String>,
+
+    /// Open the page comparing two refs given as BASE..HEAD or BASE...HEAD
+    #[arg(long, value_name = "RANGE", group = "page")]
+    compare: Option<String>,
+
+    /// Open the CI (Actions or pipelines) page
+    #[arg(long, group = "page")]
+    ci: bool,
+
+    /// Open the page for filing a new issue
+    #[arg(long, group = "page")]
+    new_issue: bool,
+
+    /// Open the wiki
+    #[arg(long, group = "page")]
+    wiki: bool,
+
+    /// Open the repo's settings page
+    #[arg(long, group = "page")]
+    settings: bool,
+
+    /// Open the repo in the provider's web editor (github.dev or GitLab's Web IDE)
+    #[arg(long, visible_alias = "editor", group = "page")]
+    edit: bool,
+
+    /// Open the page for each FILE (or directory) on the default branch (or
+    /// --branch), optionally at a line or range of lines like `src/main.rs:42`
+    /// or `src/main.rs:42-60`
+    #[arg(value_name = "FILE[:LINES]", conflicts_with = "page")]
+    files: Vec<String>,
+
+    /// Open FILE as a directory listing (tree) or a file (blob) instead of
+    /// going by what it is on disk
+    #[arg(long, value_enum, value_name = "STYLE", requires = "files")]
+    path_style: Option<PathStyle>,
+
+    /// Open the raw contents of FILE (on raw.githubusercontent.com for
+    /// GitHub) instead of its page, ignoring any line numbers
+    #[arg(long, requires = "files", conflicts_with = "path_style")]
+    raw: bool,
+
+    /// Build the URL from TEMPLATE, e.g. `https://{host}/{path}/tree/{branch}`,
+    /// filling in {host}, {path}, {branch}, {sha}, {file}, and {line}
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with_all = [
+            "page",
+            "path_style",
+            "raw",
+            "open_remote_page_only",
+            "remote_url_type",
+            "all",
+            "which",
+            "suggest_mirrors",
+        ]
+    )]
+    template: Option<String>,
+
+    /// Only ever open the repo's home page, never a branch, file, or other page
+    #[arg(long, conflicts_with_all = ["branch", "files", "page"])]
+    open_remote_page_only: bool,
+
+    /// Use NAME as the default branch, e.g. for FILE pages, instead of asking
+    /// git
+    #[arg(long, value_name = "NAME")]
+    default_branch: Option<String>,
+
+    /// Run as if started in PATH instead of the current directory
+    #[arg(short = 'C', long, value_name = "PATH")]
+    path: Option<PathBuf>,
+
+    /// Use the web page of every remote instead of choosing one
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "remote", "no_default", "interactive_action", "branch", "files", "page",
+        ]
+    )]
+    all: bool,
+
+    /// Read remote URLs with `git remote get-url` instead of `git remote --verbose`
+    #[arg(long)]
+    get_url: bool,
+
+    /// Only use the URLs remotes fetch from, not those they push to
+    #[arg(long, conflicts_with_all = ["push_url_only", "url"])]
+    fetch_url_only: bool,
+
+    /// Only use the URLs remotes push to, not those they fetch from
+    #[arg(long, conflicts_with = "url")]
+    push_url_only: bool,
+
+    /// Replace ssh `Host` aliases from ~/.ssh/config with their `HostName`
+    #[arg(long)]
+    ssh_config: bool,
+
+    /// Convert this remote URL (or one read from stdin if it's `-`) instead of
+    /// finding the repo's remotes; git isn't needed
+    #[arg(
+        long,
+        value_name = "URL",
+        conflicts_with_all = [
+            "remote", "no_default", "branch", "files", "commit", "tag", "compare", "path", "all", "get_url",
+        ]
+    )]
+    url: Option<String>,
+
+    /// Print all remotes and their web URLs as JSON instead of opening one
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "action", "remote", "no_default", "branch", "files", "page", "all", "url",
+        ]
+    )]
+    json: bool,
+
+    /// Print how many remotes there are and which would be chosen without
+    /// prompting, instead of opening one
+    #[arg(
+        long,
+        visible_alias = "count",
+        conflicts_with_all = ["action", "branch", "files", "page", "all", "url", "json"]
+    )]
+    which: bool,
+
+    /// Print the `git remote add` commands that would mirror the repo to
+    /// github.com, gitlab.com, bitbucket.org, and codeberg.org at the same
+    /// path, skipping those it already has a remote for; nothing is run
+    #[arg(
+        long,
+        conflicts_with_all = ["action", "branch", "files", "page", "all", "url", "json", "which"]
+    )]
+    suggest_mirrors: bool,
+
+    /// Print a completion script for SHELL and exit
+    #[arg(long, value_name = "SHELL")]
+    completions: Option<Shell>,
+
+    /// Keep the trailing `.git` on the converted URL
+    #[arg(long)]
+    keep_git: bool,
+
+    /// Strip the trailing `.git` even if the config file says to keep it
+    #[arg(long, conflicts_with = "keep_git")]
+    strip_git: bool,
+
+    /// Use SCHEME (http or https) for URLs converted from SSH remotes; http
+    /// also downgrades https remotes, for instances only served over http
+    #[arg(long, value_name = "SCHEME", default_value_t)]
+    scheme: Scheme,
+
+    /// Convert the remote to its web page or to a URL to clone it with
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TYPE",
+        default_value_t,
+        conflicts_with_all = ["branch", "files", "page"]
+    )]
+    remote_url_type: UrlType,
+
+    /// Don't wait for the browser command to exit, so its failures go unreported
+    #[arg(long)]
+    detach: bool,
+
+    /// Wait at most SECS for the browser command to exit [default: 5]
+    #[arg(long, value_name = "SECS", conflicts_with = "detach")]
+    browser_timeout: Option<u64>,
+
+    /// Ask before --all or several FILEs open more than COUNT URLs, or refuse
+    /// without a terminal [default: 10]
+    #[arg(long, value_name = "COUNT")]
+    open_count: Option<usize>,
+
+    /// Make a HEAD request to the URL first and warn if it doesn't exist
+    #[cfg(feature = "check")]
+    #[arg(long)]
+    check: bool,
+
+    /// Show how the URL was found on stderr
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Don't print informational messages or warnings, only errors
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+}
+
+/// Which kind of URL to convert the remote to.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum UrlType {
+    /// The repo's web page
+    #[default]
+    Web,
+    /// The https URL to clone with, ending in `.git`
+    HttpsClone,
+    /// The scp-style ssh URL to clone with, like `git@host:path.git`
+    SshClone,
+}
+
+/// Which kind of page to open for a path.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum PathStyle {
+    /// A directory listing, e.g. `/tree/<branch>/<dir>`
+    Tree,
+    /// A file, e.g. `/blob/<branch>/<file>`
+    Blob,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a table of the remotes, their URLs, and their web URLs
+    List,
+}
+
+impl Cli {
+    fn action(&self) -> Option<Action> {
+        if self.open {
+            Some(Action::Open)
+        } else if self.print || self.print_name {
+            Some(Action::Print)
+        } else if self.copy {
+            Some(Action::Copy)
+        } else {
+            None
+        }
+    }
+
+    fn launch(&self) -> Launch {
+        match self.browser_timeout {
+            _ if self.detach => Launch::Detach,
+            Some(secs) => Launch::Wait(Duration::from_secs(secs)),
+            None => Launch::default(),
+        }
+    }
+
+    fn strip_git(&self) -> Option<bool> {
+        if self.keep_git {
+            Some(false)
+        } else if self.strip_git {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+fn log(verbose: bool, msg: &str) {
+    if verbose {
+        eprintln!("{}", msg);
+    }
+}
+
+/// Where informational messages and warnings go: stderr, or nowhere with
+/// `--quiet`.
+fn notices(quiet: bool) -> Box<dyn Write> {
+    if quiet {
+        Box::new(io::sink())
+    } else {
+        Box::new(io::stderr())
+    }
+}
+
+/// Prints an informational message or warning unless `quiet`.
+fn notify(quiet: bool, msg: &str) -> io::Result<()> {
+    writeln!(notices(quiet), "{}", msg)
+}
+
+/// Appends the provider-specific `path` to `url`, or warns that it is unknown
+/// and leaves `url` at the repo root.
+fn append_page<T: AsRef<str>>(quiet: bool, url: &mut String, path: Option<T>) -> io::Result<()> {
+    match path {
+        Some(path) => *url = page_url(url, path.as_ref()),
+        None => notify(
+            quiet,
+            &format!("Unrecognized provider for {}, opening the repo root", url),
+        )?,
+    }
+    Ok(())
+}
+
+/// Lists the remotes of `git`, with `git remote get-url` if asked to and it
+/// works, otherwise from `git remote --verbose`.
+fn load_remotes(cli: &Cli, config: &Config, git: &GitContext) -> Result<Vec<Remote>> {
+    let from_get_url = if config.get_url(cli.get_url) {
+        match git.remotes_from_get_url() {
+            Ok(remotes) => Some(remotes),
             Err(e) => {
-                writeln!(io::stderr(), "{}", e)?;
+                log(cli.verbose, &format!("`git remote get-url` failed: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let remotes = match from_get_url {
+        Some(remotes) => remotes,
+        None => {
+            let raw_output = git.remote_output()?;
+            log(
+                cli.verbose,
+                &format!("`git remote --verbose` output:\n{}", raw_output.trim_end()),
+            );
+            urls_from_output(raw_output)
+        }
+    };
+    log(
+        cli.verbose,
+        &format!(
+            "Parsed remotes:\n{}",
+            remote_choices(&remotes)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    );
+    Ok(remotes)
+}
+
+/// Writes the command that `runner` would open `url` in a browser with to
+/// `out`, for `--show-command`.
+fn show_command(runner: &dyn CommandRunner, url: &str, out: &mut dyn Write) -> Result<()> {
+    writeln!(
+        out,
+        "{}",
+        command_line(&open_command_with(runner, OS, url)?)
+    )?;
+    Ok(())
+}
+
+/// Opens `url` in a browser with `runner`. If the browser fails and
+/// `interactive`, offers to write the URL to `out` instead, reading the answer
+/// from `input`.
+fn open_or_print<R: BufRead>(
+    runner: &dyn CommandRunner,
+    url: &str,
+    launch: Launch,
+    interactive: bool,
+    input: &mut R,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match open_url_with(runner, url, launch) {
+        Err(e) if offer_print_fallback(&e, interactive) => {
+            writeln!(io::stderr(), "{}", e)?;
+            if !confirm_from_reader("Print the URL instead?", input)? {
+                return Err(e);
             }
+            writeln!(out, "{}", url)?;
+            Ok(())
         }
-        input.clear();
+        result => result,
+    }
+}
+
+/// The action set by `$GIT_REPO_ACTION`, if it's set and not empty.
+fn env_action() -> Result<Option<Action>> {
+    match env::var("GIT_REPO_ACTION") {
+        Ok(name) if !name.is_empty() => name
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("Invalid GIT_REPO_ACTION: {}", e).into()),
+        _ => Ok(None),
+    }
+}
+
+/// The remote URL given to `--url`, read from `input` if it's `-`.
+fn url_arg<R: BufRead>(url: &str, input: &mut R) -> Result<String> {
+    if url != "-" {
+        return Ok(url.to_owned());
+    }
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    match line.trim() {
+        "" => Err("No URL on stdin".into()),
+        url => Ok(url.to_owned()),
+    }
+}
+
+/// Converts the web URL `url` to the type chosen with `--remote-url-type`,
+/// using the `--scheme` for https ones.
+fn url_of_type(cli: &Cli, url: String) -> Result<String> {
+    match cli.remote_url_type {
+        UrlType::Web => Ok(cli.scheme.apply(url)),
+        UrlType::HttpsClone => Ok(cli.scheme.apply(https_clone_url(&url)?)),
+        UrlType::SshClone => ssh_url(&url),
     }
 }
 
-fn choose_remote_url<T: AsRef<str>>(urls: &HashSet<T>) -> Result<&str> {
-    match urls.len() {
-        0 => Err("No URL found".into()),
-        1 => Ok(urls.iter().next().unwrap().as_ref()),
-        _ => {
-            let url = select_from_list(&mut urls.iter())?;
-            Ok(url.as_ref())
+/// The gist page for the web URL `url` on `provider` if it looks like a gist
+/// cloned from github.com, otherwise `url`. Clone URLs are left alone.
+fn gist_or_url(cli: &Cli, provider: Provider, url: String) -> String {
+    if cli.remote_url_type != UrlType::Web {
+        return url;
+    }
+    match gist_url(provider, &url) {
+        Some(gist) => {
+            log(
+                cli.verbose,
+                &format!("{} looks like a gist, using {}", url, gist),
+            );
+            gist
         }
+        None => url,
     }
 }
 
-fn git_output() -> Result<String> {
-    Ok(String::from_utf8_lossy(
-        &Command::new("git")
-            .args("remote --verbose".split_whitespace())
-            .output()?
-            .stdout,
-    )
-    .into_owned())
+/// The FILE argument `arg` resolved against `-C`, along with its path
+/// relative to the repo root and its line numbers.
+fn repo_file(arg: &str, git: &GitContext) -> Result<(PathBuf, String, Option<Lines>)> {
+    let (file, lines) = split_lines(arg)?;
+    let file = match git.dir() {
+        Some(dir) if Path::new(file).is_relative() => dir.join(file),
+        _ => PathBuf::from(file),
+    };
+    let relative = repo_relative_path(&git.toplevel()?, &file)?;
+    Ok((file, relative, lines))
+}
+
+/// The branch to show FILE on: the one given to `--branch`, the pushed one
+/// for a bare `--branch`, otherwise the default branch.
+fn file_branch(cli: &Cli, git: &GitContext, remote_name: &str) -> Result<String> {
+    Ok(match &cli.branch {
+        Some(Some(branch)) => branch.to_owned(),
+        Some(None) => git
+            .pushed_branch(remote_name)?
+            .unwrap_or_else(|| git.default_branch(remote_name)),
+        None => git.default_branch(remote_name),
+    })
 }
 
-fn urls_from_output<T: AsRef<str>>(output: T) -> HashSet<String> {
-    output
-        .as_ref()
-        .lines()
-        .filter_map(|line| line.split_whitespace().nth(1).map(Into::into))
-        .collect::<HashSet<_>>()
+/// The value of the `--template` placeholder `name` for the repo at the web
+/// URL `url` and the FILE argument `file`, if any.
+fn template_value(
+    cli: &Cli,
+    git: &GitContext,
+    remote_name: &str,
+    url: &str,
+    file: Option<&str>,
+    name: &str,
+) -> Result<String> {
+    let no_value = || format!("The template uses {{{}}}, but {} has none", name, url);
+    match name {
+        "host" => url_host(url)
+            .map(String::from)
+            .ok_or_else(|| no_value().into()),
+        "path" => url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.split_once('/'))
+            .map(|(_, path)| path.to_owned())
+            .ok_or_else(|| no_value().into()),
+        "branch" => file_branch(cli, git, remote_name),
+        "sha" => git.commit_sha("HEAD"),
+        "file" => file
+            .map(|file| repo_file(file, git))
+            .transpose()?
+            .map(|(_, file, _)| file)
+            .ok_or_else(|| "The template uses {file}, so pass FILE".into()),
+        "line" => file
+            .map(|file| repo_file(file, git))
+            .transpose()?
+            .and_then(|(_, _, lines)| lines)
+            .map(|lines| lines.start.to_string())
+            .ok_or_else(|| "The template uses {line}, so pass FILE:LINE".into()),
+        _ => Err(format!("Unknown placeholder {{{}}}", name).into()),
+    }
 }
 
-fn format_url<T: AsRef<str>>(url: T) -> String {
-    if url.as_ref().contains(':') {
-        let mut iter = url.as_ref().splitn(2, ':');
-        let (user_and_domain, path) = (iter.next(), iter.next());
-        let domain = user_and_domain.and_then(|x| x.splitn(2, '@').nth(1));
-        match (domain, path) {
-            (Some(domain), Some(path)) if !(domain.is_empty() || path.is_empty()) => {
-                return format!("https://{domain}/{path}", domain = domain, path = path)
+/// Appends the page chosen by `cli`, if any, to the repo `url` on `provider`,
+/// with `file` as the FILE argument.
+fn append_pages(
+    cli: &Cli,
+    git: &GitContext,
+    remote_name: &str,
+    provider: Provider,
+    file: Option<&str>,
+    url: &mut String,
+) -> Result<()> {
+    if let Some((path, file, lines)) = file.map(|file| repo_file(file, git)).transpose()? {
+        let style = cli.path_style.unwrap_or(if path.is_dir() {
+            PathStyle::Tree
+        } else {
+            PathStyle::Blob
+        });
+        let branch = file_branch(cli, git, remote_name)?;
+        let page = match style {
+            PathStyle::Tree if cli.raw => {
+                return Err("Raw contents only apply to files, not directories".into())
             }
-            _ => (),
+            PathStyle::Tree if lines.is_some() => {
+                return Err("Line numbers only apply to files, not directories".into())
+            }
+            PathStyle::Tree => provider.dir_path(&branch, &file),
+            PathStyle::Blob => provider.blob_path(&branch, &file, lines),
+        };
+        if !cli.raw {
+            append_page(cli.quiet, url, page)?;
+        } else if let Some(raw) = raw_url(provider, url, &branch, &file) {
+            *url = raw;
+        } else {
+            notify(
+                cli.quiet,
+                &format!("No raw file URL known for {}, opening the file page", url),
+            )?;
+            append_page(cli.quiet, url, page)?;
+        }
+    } else if let Some(branch) = &cli.branch {
+        match branch.as_ref().map_or_else(
+            || git.pushed_branch(remote_name),
+            |branch| Ok(Some(branch.to_owned())),
+        )? {
+            Some(branch) => *url = build_branch_url(provider, url, &branch),
+            None => notify(cli.quiet, "HEAD is detached, opening the repo root")?,
+        }
+    }
+    if let Some(rev) = &cli.commit {
+        let sha = git.commit_sha(rev.as_deref().unwrap_or("HEAD"))?;
+        append_page(cli.quiet, url, provider.commit_path(&sha))?;
+    }
+    if let Some(tag) = &cli.tag {
+        git.verify_tag(tag)?;
+        append_page(cli.quiet, url, provider.tag_path(tag))?;
+    }
+    if let Some(range) = &cli.compare {
+        let (base, head) = split_range(range)?;
+        git.verify_ref(remote_name, base)?;
+        git.verify_ref(remote_name, head)?;
+        append_page(cli.quiet, url, provider.compare_path(base, head))?;
+    }
+    if cli.pulls {
+        append_page(cli.quiet, url, provider.pulls_path())?;
+    }
+    if cli.ci {
+        append_page(cli.quiet, url, provider.ci_path())?;
+    }
+    if cli.new_issue {
+        append_page(cli.quiet, url, provider.new_issue_path())?;
+    }
+    if cli.wiki {
+        append_page(cli.quiet, url, provider.wiki_path())?;
+    }
+    if cli.settings {
+        append_page(cli.quiet, url, provider.settings_path())?;
+    }
+    if cli.edit {
+        match editor_url(provider, url) {
+            Some(editor) => *url = editor,
+            None => notify(
+                cli.quiet,
+                &format!("No web editor known for {}, opening the repo root", url),
+            )?,
         }
     }
-    String::from(url.as_ref())
+    Ok(())
+}
+
+/// The command line arguments without a leading `repo`, which wrappers that
+/// run `git-repo` on behalf of `git repo` may pass along. `git` itself
+/// doesn't, but `git-repo repo` has no other meaning, since a file named
+/// `repo` can be given as `./repo`.
+fn args_without_subcommand(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut args: Vec<OsString> = args.into_iter().collect();
+    if args.get(1).is_some_and(|arg| arg == "repo") {
+        args.remove(1);
+    }
+    args
 }
 
-fn open_url<T: AsRef<str>>(url: T) -> Result<ExitStatus> {
-    let mut cmd = match OS {
-        "macos" => Command::new("open"),
-        "linux" => {
-            let browser = env::var("BROWSER").unwrap_or_else(|_| "firefox".to_owned());
-            Command::new(browser)
+/// Prints the error, if any, and exits with its [`git_repo::Error::exit_code`].
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        process::exit(e.exit_code());
+    }
+}
+
+fn run() -> Result<()> {
+    git_repo::logging::init(env::var("GIT_REPO_LOG").ok().as_deref());
+    let mut cli = Cli::parse_from(args_without_subcommand(env::args_os()));
+    if let Some(shell) = cli.completions {
+        clap_complete::generate(shell, &mut Cli::command(), "git-repo", &mut io::stdout());
+        return Ok(());
+    }
+    let dir = cli.path.as_deref();
+    if let Some(dir) = dir {
+        if !dir.exists() {
+            return Err(format!("{} does not exist", dir.display()).into());
         }
-        _ => unimplemented!("so far this only works on Mac or Linux"),
-    };
-    Ok(cmd.arg(url.as_ref()).status()?)
+        if !dir.is_dir() {
+            return Err(format!("{} is not a directory", dir.display()).into());
+        }
+    }
+    let mut config = Config::load()?;
+    if let Some(action) = env_action()? {
+        config.default_action = Some(action);
+    }
+    if let Some(mappings) = env::var_os("GIT_REPO_HOST_MAP") {
+        config
+            .hosts
+            .extend(HostMap::parse(&mappings.to_string_lossy())?);
+    }
+    let mut input = io::BufReader::new(io::stdin());
+    if let Some(url) = &cli.url {
+        cli.url = Some(url_arg(url, &mut input)?);
+    }
+    run_with(
+        &cli,
+        &config,
+        &SystemRunner,
+        io::stdin().is_terminal(),
+        &mut input,
+        &mut io::stdout(),
+    )
 }
 
-fn main() -> Result<()> {
-    let raw_output = git_output()?;
-    let urls = urls_from_output(raw_output);
-    let url = choose_remote_url(&urls)?;
-    open_url(format_url(url))?;
+/// Does what `cli` asks with `config`, running git and the browser with
+/// `runner` and writing what's printed to `out`. Nothing is asked unless
+/// `interactive`, and then answers are read from `input`. The environment
+/// isn't read here: `run` has already applied it to `cli` and `config`.
+fn run_with(
+    cli: &Cli,
+    config: &Config,
+    runner: &dyn CommandRunner,
+    interactive: bool,
+    mut input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let git = GitContext::new(runner, cli.path.as_deref())
+        .with_default_branch(cli.default_branch.clone());
+    let action = config.action(cli.action());
+    let strip_git = config.strip_git(cli.strip_git());
+    let launch = cli.launch();
+    let prompt_action = cli.action().is_none()
+        && !cli.show_command
+        && config.interactive_action(cli.interactive_action);
+    let remotes = match cli.url {
+        Some(_) => Vec::new(),
+        None => load_remotes(cli, config, &git)?,
+    };
+    if cli.json {
+        let listing = remote_listing(&remotes, strip_git);
+        writeln!(out, "{}", serde_json::to_string_pretty(&listing)?)?;
+        return Ok(());
+    }
+    if let Some(Command::List) = cli.command {
+        write!(
+            out,
+            "{}",
+            listing_table(&remote_listing(&remotes, strip_git))
+        )?;
+        if remotes.is_empty() {
+            notify(cli.quiet, "This repository has no remotes")?;
+        }
+        return Ok(());
+    }
+    let ssh_config = if config.ssh_config(cli.ssh_config) {
+        SshConfig::load()?
+    } else {
+        SshConfig::default()
+    };
+    let remotes = remotes.into_iter().map(|remote| {
+        if cli.fetch_url_only {
+            remote.fetch_only()
+        } else if cli.push_url_only {
+            remote.push_only()
+        } else {
+            remote
+        }
+    });
+    let mut remotes = retain_web_remotes(remotes.collect(), &mut notices(cli.quiet))?;
+    for remote in &mut remotes {
+        for url in remote.fetch_urls.iter_mut().chain(&mut remote.push_urls) {
+            *url = ssh_config.expand(url);
+        }
+    }
+    let selector = RemoteSelector {
+        name: if cli.no_default {
+            None
+        } else {
+            config.remote(cli.remote.as_deref())
+        },
+        interactive,
+        no_default: cli.no_default,
+    };
+    if cli.which {
+        writeln!(out, "Remotes: {}", remotes.len())?;
+        let selector = RemoteSelector {
+            interactive: false,
+            ..selector
+        };
+        match choose_remote_from(&remotes, &selector, &mut input, out) {
+            Ok(choice) => writeln!(out, "Chosen: {}", choice)?,
+            Err(Error::NeedsSelection(choices)) => writeln!(
+                out,
+                "Chosen: none, you'd be prompted to choose from {} URLs",
+                choices.len()
+            )?,
+            Err(e) => return Err(e),
+        }
+        return Ok(());
+    }
+    if cli.suggest_mirrors {
+        let choice = choose_remote_from(&remotes, &selector, &mut input, out)?;
+        log(cli.verbose, &format!("Chosen URL: {}", choice.url));
+        for command in mirror_commands(&remotes, choice.url)? {
+            writeln!(out, "{}", command)?;
+        }
+        return Ok(());
+    }
+    let hosts = &config.hosts;
+    if cli.all {
+        let mut names = Vec::new();
+        let mut urls = Vec::new();
+        for (name, url) in named_web_urls(&remotes, strip_git)? {
+            names.push(name);
+            let url = url_of_type(cli, url)?;
+            let provider = url_host(&url).map_or(Provider::Generic, |host| hosts.provider(host));
+            urls.push(gist_or_url(cli, provider, url));
+        }
+        if cli.show_command {
+            return urls
+                .iter()
+                .try_for_each(|url| show_command(runner, url, out));
+        }
+        match action {
+            Action::Print if cli.print_name => {
+                for (name, url) in names.iter().zip(&urls) {
+                    writeln!(out, "{}\t{}", name, url)?;
+                }
+            }
+            Action::Print => {
+                for url in &urls {
+                    writeln!(out, "{}", url)?;
+                }
+            }
+            Action::Copy => {
+                let urls = urls.join("\n");
+                copy_to_clipboard(&urls)?;
+                notify(cli.quiet, &format!("Copied {} to the clipboard", urls))?;
+            }
+            Action::Open => {
+                confirm_open_count(
+                    urls.len(),
+                    config.open_count(cli.open_count),
+                    interactive,
+                    &mut input,
+                )?;
+                for url in &urls {
+                    if cli.confirm {
+                        confirm_open_url(url, interactive, &mut input)?;
+                    }
+                    open_or_print(runner, url, launch, interactive, &mut input, out)?;
+                }
+            }
+        }
+        return Ok(());
+    }
+    let (remote_url, remote_name) = match &cli.url {
+        Some(url) => (ssh_config.expand(url), DEFAULT_REMOTES[0]),
+        None => {
+            let choice = choose_remote_from(&remotes, &selector, &mut input, out)?;
+            log(cli.verbose, &format!("Chosen URL: {}", choice.url));
+            (choice.url.to_owned(), choice.name)
+        }
+    };
+    let mut url = url_of_type(cli, format_url(&remote_url, strip_git)?)?;
+    let provider = url_host(&url).map_or(Provider::Generic, |host| hosts.provider(host));
+    url = gist_or_url(cli, provider, url);
+    // One page per FILE, or just the one without any.
+    let files: Vec<Option<&str>> = match cli.files.as_slice() {
+        [] => vec![None],
+        files => files.iter().map(|file| Some(file.as_str())).collect(),
+    };
+    let mut pages = Vec::new();
+    for file in files {
+        let mut page = url.clone();
+        if let Some(template) = &cli.template {
+            page = render_template(template, |name| {
+                template_value(cli, &git, remote_name, &url, file, name)
+            })?;
+        } else if !cli.open_remote_page_only {
+            append_pages(cli, &git, remote_name, provider, file, &mut page)?;
+        }
+        log(cli.verbose, &format!("Formatted URL: {}", page));
+        #[cfg(feature = "check")]
+        if cli.check {
+            git_repo::check_url(&page, &mut notices(cli.quiet))?;
+        }
+        pages.push(page);
+    }
+    if cli.show_command {
+        return pages
+            .iter()
+            .try_for_each(|url| show_command(runner, url, out));
+    }
+    let action = if prompt_action && interactive {
+        select_from(
+            Action::ALL,
+            Some("Choose an action by number:"),
+            DEFAULT_MAX_ATTEMPTS,
+            &mut input,
+            out,
+        )?
+    } else {
+        action
+    };
+    match action {
+        Action::Print if cli.print_name => {
+            for url in &pages {
+                writeln!(out, "{}\t{}", remote_name, url)?;
+            }
+        }
+        Action::Print => {
+            for url in &pages {
+                writeln!(out, "{}", url)?;
+            }
+        }
+        Action::Copy => {
+            let urls = pages.join("\n");
+            copy_to_clipboard(&urls)?;
+            notify(cli.quiet, &format!("Copied {} to the clipboard", urls))?;
+        }
+        Action::Open => {
+            confirm_open_count(
+                pages.len(),
+                config.open_count(cli.open_count),
+                interactive,
+                &mut input,
+            )?;
+            for url in &pages {
+                if cli.confirm {
+                    confirm_open_url(url, interactive, &mut input)?;
+                }
+                open_or_print(runner, url, launch, interactive, &mut input, out)?;
+            }
+        }
+    }
     Ok(())
 }
 
+#[cfg(test)]
+#[path = "runner/mock.rs"]
+mod mock;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mock::{output, MockRunner};
+    use std::ffi::OsStr;
+    use std::io::Cursor;
+
+    const REMOTES: &str = "\
+upstream\tgit@gitlab.com:n8henrie/git-repo.git (fetch)
+upstream\tgit@gitlab.com:n8henrie/git-repo.git (push)
+origin\tgit@github.com:n8henrie/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+";
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    /// Answers `git rev-parse` with [`SHA`] and any other git command with
+    /// [`REMOTES`].
+    fn mock_runner() -> MockRunner {
+        MockRunner::replying(|args| {
+            if args.iter().any(|arg| arg == "rev-parse") {
+                output(&format!("{}\n", SHA), "")
+            } else {
+                output(REMOTES, "")
+            }
+        })
+    }
+
+    /// Runs with the arguments `args` and `runner`, returning what's printed.
+    /// It's interactive if there's `input` to answer with.
+    fn run_mocked(runner: &MockRunner, args: &[&str], input: Option<&str>) -> Result<String> {
+        let cli = Cli::parse_from(std::iter::once("git-repo").chain(args.iter().copied()));
+        let mut out = Vec::new();
+        run_with(
+            &cli,
+            &Config::default(),
+            runner,
+            input.is_some(),
+            &mut Cursor::new(input.unwrap_or_default()),
+            &mut out,
+        )?;
+        Ok(String::from_utf8(out).expect("output is UTF-8"))
+    }
+
     #[test]
-    fn test_choose_url() -> Result<()> {
-        let mut hs = <HashSet<&str>>::new();
-        hs.insert("https://n8henrie.com");
-        assert_eq!(choose_remote_url(&hs)?, "https://n8henrie.com");
+    fn test_run_print_page() -> Result<()> {
+        let runner = mock_runner();
+        assert_eq!(
+            run_mocked(&runner, &["--print", "--pulls"], None)?,
+            "https://github.com/n8henrie/git-repo/pulls\n"
+        );
+        assert_eq!(runner.runs.borrow()[0], ["remote", "--verbose"]);
+        assert!(runner.launches.borrow().is_empty());
+
+        assert_eq!(
+            run_mocked(
+                &runner,
+                &["--print", "--remote", "upstream", "--commit"],
+                None
+            )?,
+            format!("https://gitlab.com/n8henrie/git-repo/-/commit/{}\n", SHA)
+        );
         Ok(())
     }
 
     #[test]
-    fn test_urls_from_output() {
-        let input = "n8henrie        git@gitlab.com:n8henrie/git-repo.git (fetch)
-n8henrie        git@gitlab.com:n8henrie/git-repo.git (push)
-origin  git@github.com:n8henrie/git-repo.git (fetch)
-origin  git@github.com:n8henrie/git-repo.git (push)";
-        let output: HashSet<String> = [
-            "git@gitlab.com:n8henrie/git-repo.git",
-            "git@github.com:n8henrie/git-repo.git",
-        ]
-        .iter()
-        .cloned()
-        .map(String::from)
-        .collect();
-        assert_eq!(urls_from_output(input), output)
+    fn test_run_print_tag() -> Result<()> {
+        let runner = mock_runner();
+        assert_eq!(
+            run_mocked(&runner, &["--print", "--tag", "v1.0"], None)?,
+            "https://github.com/n8henrie/git-repo/releases/tag/v1.0\n"
+        );
+        assert_eq!(
+            runner.runs.borrow()[1],
+            ["rev-parse", "--verify", "--quiet", "refs/tags/v1.0"]
+        );
+
+        let runner = MockRunner::replying(|args| match args[0].to_str() {
+            Some("rev-parse") => mock::failure(""),
+            _ => output(REMOTES, ""),
+        });
+        let err = run_mocked(&runner, &["--print", "--tag", "v1.0"], None).unwrap_err();
+        assert!(err.to_string().starts_with("No tag named"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_print_compare() -> Result<()> {
+        let runner = mock_runner();
+        assert_eq!(
+            run_mocked(&runner, &["--print", "--compare", "main..feature"], None)?,
+            "https://github.com/n8henrie/git-repo/compare/main...feature\n"
+        );
+        assert_eq!(runner.run_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_get_url() -> Result<()> {
+        let runner = MockRunner::replying(|args| {
+            let args: Vec<_> = args.iter().filter_map(|arg| arg.to_str()).collect();
+            match args[..] {
+                ["remote"] => output("origin\n", ""),
+                ["remote", "get-url", "--all", "origin"] => {
+                    output("https://github.com/upstream/git-repo.git\n", "")
+                }
+                ["remote", "get-url", "--push", "--all", "origin"] => {
+                    output("git@github.com:n8henrie/git-repo.git\n", "")
+                }
+                _ => output(REMOTES, ""),
+            }
+        });
+        assert_eq!(
+            run_mocked(&runner, &["--print", "--get-url", "--fetch-url-only"], None)?,
+            "https://github.com/upstream/git-repo\n"
+        );
+        assert_eq!(runner.run_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_print_all() -> Result<()> {
+        let runner = mock_runner();
+        assert_eq!(
+            run_mocked(&runner, &["--print-name", "--all"], None)?,
+            "upstream\thttps://gitlab.com/n8henrie/git-repo\n\
+             origin\thttps://github.com/n8henrie/git-repo\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_open() -> Result<()> {
+        let runner = mock_runner();
+        assert_eq!(run_mocked(&runner, &["--open", "--ci"], None)?, "");
+        let launches = runner.launches.borrow();
+        assert_eq!(launches.len(), 1);
+        assert_eq!(
+            launches[0].last().map(OsString::as_os_str),
+            Some(OsStr::new("https://github.com/n8henrie/git-repo/actions"))
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_format_url() {
+    fn test_run_open_all_print_fallback() -> Result<()> {
+        let mut runner = mock_runner();
+        runner.browser_fails = true;
         assert_eq!(
-            format_url("git@github.com:n8henrie/git-repo.git"),
-            "https://github.com/n8henrie/git-repo.git"
+            run_mocked(&runner, &["--open", "--all"], Some("y\ny\n"))?,
+            "https://gitlab.com/n8henrie/git-repo\n\
+             https://github.com/n8henrie/git-repo\n"
         );
+        assert_eq!(runner.launches.borrow().len(), 2);
+
+        let err = run_mocked(&runner, &["--open", "--all"], Some("n\n")).unwrap_err();
+        assert!(matches!(err, Error::BrowserFailed { .. }));
+        let err = run_mocked(&runner, &["--open", "--all"], None).unwrap_err();
+        assert!(matches!(err, Error::BrowserFailed { .. }));
+        assert_eq!(runner.launches.borrow().len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_prompts_on_input() -> Result<()> {
+        let runner = mock_runner();
         assert_eq!(
-            format_url("git@gitlab.com:n8henrie/git-repo.git"),
-            "https://gitlab.com/n8henrie/git-repo.git"
+            run_mocked(&runner, &["--print", "--no-default"], Some("1\n"))?,
+            "0: upstream: git@gitlab.com:n8henrie/git-repo.git\n\
+             1: origin: git@github.com:n8henrie/git-repo.git\n\
+             Choose a remote by number: https://github.com/n8henrie/git-repo\n"
         );
         assert_eq!(
-            format_url("https://gitlab.com/n8henrie/git-repo.git"),
-            "https://gitlab.com/n8henrie/git-repo.git"
+            run_mocked(&runner, &["--interactive-action"], Some("1\n"))?,
+            "0: Open in a browser\n1: Print\n2: Copy to the clipboard\n\
+             Choose an action by number: https://github.com/n8henrie/git-repo\n"
         );
+        assert!(runner.launches.borrow().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_url_arg() -> Result<()> {
+        let mut input = Cursor::new("git@github.com:n8henrie/git-repo.git\n");
+        assert_eq!(url_arg("origin-url", &mut input)?, "origin-url");
+        assert_eq!(
+            url_arg("-", &mut input)?,
+            "git@github.com:n8henrie/git-repo.git"
+        );
+        let err = url_arg("-", &mut input).unwrap_err();
+        assert_eq!(err.to_string(), "No URL on stdin");
+        Ok(())
     }
 }
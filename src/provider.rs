@@ -0,0 +1,850 @@
+//! Hosting providers and the provider-specific parts of their web URLs.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::{encode_path, percent_encode, strip_git_suffix, Lines, Result};
+
+/// A git hosting provider, which determines the layout of its web pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Provider {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    AzureDevOps,
+    /// Gitea or Forgejo, which mirror GitHub's paths except that branch and
+    /// file pages live under `/src/branch`.
+    Gitea,
+    /// An unrecognized host; only the repo root is known to exist.
+    Generic,
+}
+
+/// Detects the provider from the host of a web URL, e.g. `github.com`.
+pub fn provider_from_host(host: &str) -> Provider {
+    match host {
+        "github.com" => Provider::GitHub,
+        "gitlab.com" => Provider::GitLab,
+        "bitbucket.org" => Provider::Bitbucket,
+        "dev.azure.com" => Provider::AzureDevOps,
+        "codeberg.org" => Provider::Gitea,
+        _ => Provider::Generic,
+    }
+}
+
+/// Appends the page `path` (from one of the [`Provider`] methods) to the repo
+/// URL `base`. Pages are never under a trailing `.git`, so it's dropped even if
+/// the repo URL keeps it.
+pub fn page_url(base: &str, path: &str) -> String {
+    format!("{}{}", strip_git_suffix(base), path)
+}
+
+/// The URL of the web-based editor for the repo at the web URL `base`:
+/// github.dev for github.com or GitLab's Web IDE. `None` for other providers,
+/// including GitHub Enterprise hosts.
+pub fn editor_url(provider: Provider, base: &str) -> Option<String> {
+    let base = strip_git_suffix(base);
+    match provider {
+        Provider::GitHub => base
+            .strip_prefix("https://github.com/")
+            .map(|path| format!("https://github.dev/{}", path)),
+        Provider::GitLab => {
+            let (scheme, rest) = base.split_once("://")?;
+            let (host, path) = rest.split_once('/')?;
+            Some(format!("{}://{}/-/ide/project/{}/edit", scheme, host, path))
+        }
+        _ => None,
+    }
+}
+
+/// The gist.github.com page for a gist cloned from github.com, whose web URL
+/// `base` converts to `https://github.com/<hash>` and would 404. Only a single
+/// path segment of hex digits is taken for a gist hash, since repos always
+/// have an owner; `None` for anything else, including other providers.
+pub fn gist_url(provider: Provider, base: &str) -> Option<String> {
+    if provider != Provider::GitHub {
+        return None;
+    }
+    let (scheme, rest) = strip_git_suffix(base).split_once("://")?;
+    let hash = rest.strip_prefix("github.com/")?;
+    if hash.is_empty() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("{}://gist.github.com/{}", scheme, hash))
+}
+
+/// The URL of the raw contents of `file` (a `/`-separated path relative to
+/// the repo root) on `branch`, for the repo at the web URL `base`. github.com
+/// serves these from raw.githubusercontent.com; `None` for Azure DevOps and
+/// generic hosts.
+pub fn raw_url(provider: Provider, base: &str, branch: &str, file: &str) -> Option<String> {
+    let base = strip_git_suffix(base);
+    let path = format!("{}/{}", encode_path(branch), encode_path(file));
+    let prefix = match provider {
+        Provider::GitHub => match base.strip_prefix("https://github.com/") {
+            Some(repo) => {
+                return Some(format!(
+                    "https://raw.githubusercontent.com/{}/{}",
+                    repo, path
+                ))
+            }
+            None => "/raw",
+        },
+        Provider::GitLab => "/-/raw",
+        Provider::Bitbucket => "/raw",
+        Provider::Gitea => "/raw/branch",
+        Provider::AzureDevOps | Provider::Generic => return None,
+    };
+    Some(format!("{}{}/{}", base, prefix, path))
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    /// Parses a provider name such as `github`, ignoring case.
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "github" => Ok(Provider::GitHub),
+            "gitlab" => Ok(Provider::GitLab),
+            "bitbucket" => Ok(Provider::Bitbucket),
+            "azure" => Ok(Provider::AzureDevOps),
+            "gitea" | "forgejo" => Ok(Provider::Gitea),
+            "generic" => Ok(Provider::Generic),
+            _ => Err(format!(
+                "Unknown provider {:?}, expected one of github, gitlab, bitbucket, azure, gitea, generic",
+                name
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Provider {
+    type Error = String;
+
+    fn try_from(name: String) -> std::result::Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+/// Providers for hosts that [`provider_from_host`] doesn't know, such as a
+/// GitHub Enterprise or self-hosted GitLab instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct HostMap(HashMap<String, Provider>);
+
+impl HostMap {
+    /// Parses a list of mappings like `github.mycorp.com=github,git.example.com=gitlab`,
+    /// as found in `$GIT_REPO_HOST_MAP`.
+    pub fn parse(mappings: &str) -> Result<Self> {
+        let mut map = HashMap::new();
+        for mapping in mappings.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let (host, provider) = mapping.split_once('=').ok_or_else(|| {
+                format!("Invalid host mapping {:?}, expected host=provider", mapping)
+            })?;
+            map.insert(host.trim().to_ascii_lowercase(), provider.trim().parse()?);
+        }
+        Ok(HostMap(map))
+    }
+
+    /// Adds the mappings from `other`, replacing those for the same hosts.
+    pub fn extend(&mut self, other: HostMap) {
+        self.0.extend(other.0);
+    }
+
+    /// The provider for `host`: the mapped one if there is one, otherwise the
+    /// one from [`provider_from_host`].
+    pub fn provider(&self, host: &str) -> Provider {
+        let provider = self
+            .0
+            .get(&host.to_ascii_lowercase())
+            .copied()
+            .unwrap_or_else(|| provider_from_host(host));
+        log::debug!("provider for {} is {:?}", host, provider);
+        provider
+    }
+}
+
+impl Provider {
+    /// The path of the page for `branch`, relative to the repo root. Generic
+    /// hosts get the `/tree/<branch>` path most forges understand.
+    ///
+    /// Branches, tags, and files are percent-encoded a segment at a time, so a
+    /// branch like `feature/x` keeps its `/` in a path; on Azure DevOps they're
+    /// query parameters and the `/` is encoded too.
+    pub fn tree_path(self, branch: &str) -> Option<String> {
+        let prefix = match self {
+            Provider::GitHub | Provider::Generic => "/tree",
+            Provider::GitLab => "/-/tree",
+            Provider::Bitbucket => "/src",
+            Provider::Gitea => "/src/branch",
+            Provider::AzureDevOps => return Some(format!("?version=GB{}", percent_encode(branch))),
+        };
+        Some(format!("{}/{}", prefix, encode_path(branch)))
+    }
+
+    /// The path of the page showing `file` (a `/`-separated path relative to
+    /// the repo root) on `branch`, relative to the repo root, highlighting
+    /// `lines` if given.
+    pub fn blob_path(self, branch: &str, file: &str, lines: Option<Lines>) -> Option<String> {
+        let prefix = match self {
+            Provider::GitHub => "/blob",
+            Provider::GitLab => "/-/blob",
+            Provider::Bitbucket => "/src",
+            Provider::Gitea => "/src/branch",
+            Provider::AzureDevOps => {
+                let lines = lines.map_or_else(String::new, |lines| self.line_anchor(lines));
+                return Some(format!(
+                    "?path=/{}&version=GB{}{}",
+                    encode_path(file),
+                    percent_encode(branch),
+                    lines
+                ));
+            }
+            Provider::Generic => return None,
+        };
+        let anchor = lines.map_or_else(String::new, |lines| self.line_anchor(lines));
+        Some(format!(
+            "{}/{}/{}{}",
+            prefix,
+            encode_path(branch),
+            encode_path(file),
+            anchor
+        ))
+    }
+
+    /// The path of the page listing the directory `dir` (a `/`-separated path
+    /// relative to the repo root, empty for the root) on `branch`, relative
+    /// to the repo root.
+    pub fn dir_path(self, branch: &str, dir: &str) -> Option<String> {
+        match self {
+            Provider::AzureDevOps => self.blob_path(branch, dir, None),
+            _ if dir.is_empty() => self.tree_path(branch),
+            _ => self
+                .tree_path(branch)
+                .map(|tree| format!("{}/{}", tree, encode_path(dir))),
+        }
+    }
+
+    /// The fragment (or, on Azure DevOps, query parameters) that highlights
+    /// `lines` on a file's page, e.g. `#L42-L60`.
+    fn line_anchor(self, lines: Lines) -> String {
+        match (self, lines.end) {
+            (Provider::AzureDevOps, end) => format!(
+                "&line={}&lineEnd={}&lineStartColumn=1&lineEndColumn=1",
+                lines.start,
+                end.map_or(lines.start + 1, |end| end + 1)
+            ),
+            (Provider::Bitbucket, None) => format!("#lines-{}", lines.start),
+            (Provider::Bitbucket, Some(end)) => format!("#lines-{}:{}", lines.start, end),
+            (Provider::GitLab, Some(end)) => format!("#L{}-{}", lines.start, end),
+            (_, Some(end)) => format!("#L{}-L{}", lines.start, end),
+            (_, None) => format!("#L{}", lines.start),
+        }
+    }
+
+    /// The path of the page for the commit `sha`, relative to the repo root.
+    pub fn commit_path(self, sha: &str) -> Option<String> {
+        let prefix = match self {
+            Provider::GitHub | Provider::Gitea => "/commit",
+            Provider::GitLab => "/-/commit",
+            Provider::Bitbucket => "/commits",
+            Provider::AzureDevOps => "/commit",
+            Provider::Generic => return None,
+        };
+        Some(format!("{}/{}", prefix, sha))
+    }
+
+    /// The path of the page for the tag `tag`, relative to the repo root.
+    pub fn tag_path(self, tag: &str) -> Option<String> {
+        match self {
+            Provider::GitHub | Provider::Gitea => {
+                Some(format!("/releases/tag/{}", encode_path(tag)))
+            }
+            Provider::GitLab => Some(format!("/-/tags/{}", encode_path(tag))),
+            Provider::Bitbucket => Some(format!("/src/{}", encode_path(tag))),
+            Provider::AzureDevOps => Some(format!("?version=GT{}", percent_encode(tag))),
+            Provider::Generic => None,
+        }
+    }
+
+    /// The path of the page comparing `head` to `base`, i.e. the changes since
+    /// they diverged, relative to the repo root. GitHub, GitLab, and Gitea
+    /// write this with three dots, `base...head`.
+    pub fn compare_path(self, base: &str, head: &str) -> Option<String> {
+        let range = format!("{}...{}", encode_path(base), encode_path(head));
+        match self {
+            Provider::GitHub | Provider::Gitea => Some(format!("/compare/{}", range)),
+            Provider::GitLab => Some(format!("/-/compare/{}", range)),
+            Provider::Bitbucket => Some(format!(
+                "/branches/compare/{}%0D{}",
+                encode_path(head),
+                encode_path(base)
+            )),
+            Provider::AzureDevOps => Some(format!(
+                "/branchCompare?baseVersion=GB{}&targetVersion=GB{}",
+                percent_encode(base),
+                percent_encode(head)
+            )),
+            Provider::Generic => None,
+        }
+    }
+
+    /// The path of the CI page, relative to the repo root. Azure DevOps keeps
+    /// pipelines per project rather than per repo, so it has none.
+    pub fn ci_path(self) -> Option<&'static str> {
+        match self {
+            Provider::GitHub | Provider::Gitea => Some("/actions"),
+            Provider::GitLab => Some("/-/pipelines"),
+            Provider::Bitbucket => Some("/addon/pipelines/home"),
+            Provider::AzureDevOps | Provider::Generic => None,
+        }
+    }
+
+    /// The path of the page for filing a new issue, relative to the repo root.
+    /// Azure DevOps tracks work items per project, so it has none.
+    pub fn new_issue_path(self) -> Option<&'static str> {
+        match self {
+            Provider::GitHub | Provider::Bitbucket | Provider::Gitea => Some("/issues/new"),
+            Provider::GitLab => Some("/-/issues/new"),
+            Provider::AzureDevOps | Provider::Generic => None,
+        }
+    }
+
+    /// The path of the wiki's home page, relative to the repo root. Azure
+    /// DevOps keeps wikis per project, so it has none.
+    pub fn wiki_path(self) -> Option<&'static str> {
+        match self {
+            Provider::GitHub | Provider::Bitbucket | Provider::Gitea => Some("/wiki"),
+            Provider::GitLab => Some("/-/wikis/home"),
+            Provider::AzureDevOps | Provider::Generic => None,
+        }
+    }
+
+    /// The path of the repo's settings page, relative to the repo root. Azure
+    /// DevOps keeps repo settings under the project, so it has none.
+    pub fn settings_path(self) -> Option<&'static str> {
+        match self {
+            Provider::GitHub | Provider::Gitea => Some("/settings"),
+            Provider::GitLab => Some("/-/edit"),
+            Provider::Bitbucket => Some("/admin"),
+            Provider::AzureDevOps | Provider::Generic => None,
+        }
+    }
+
+    /// The path of the pull (or merge) requests page, relative to the repo root.
+    pub fn pulls_path(self) -> Option<&'static str> {
+        match self {
+            Provider::GitHub | Provider::Gitea => Some("/pulls"),
+            Provider::GitLab => Some("/-/merge_requests"),
+            Provider::Bitbucket => Some("/pull-requests"),
+            Provider::AzureDevOps => Some("/pullrequests"),
+            Provider::Generic => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_from_host() {
+        assert_eq!(provider_from_host("github.com"), Provider::GitHub);
+        assert_eq!(provider_from_host("gitlab.com"), Provider::GitLab);
+        assert_eq!(provider_from_host("bitbucket.org"), Provider::Bitbucket);
+        assert_eq!(provider_from_host("codeberg.org"), Provider::Gitea);
+        assert_eq!(provider_from_host("git.example.com"), Provider::Generic);
+    }
+
+    #[test]
+    fn test_provider_from_str() {
+        assert_eq!("github".parse(), Ok(Provider::GitHub));
+        assert_eq!("GitLab".parse(), Ok(Provider::GitLab));
+        assert_eq!("bitbucket".parse(), Ok(Provider::Bitbucket));
+        assert_eq!("gitea".parse(), Ok(Provider::Gitea));
+        assert_eq!("Forgejo".parse(), Ok(Provider::Gitea));
+        assert_eq!("generic".parse(), Ok(Provider::Generic));
+        assert!("sourcehut".parse::<Provider>().is_err());
+    }
+
+    #[test]
+    fn test_gitlab_route_prefix() {
+        let gitlab = Provider::GitLab;
+        let paths = [
+            gitlab.tree_path("main"),
+            gitlab.dir_path("main", "src"),
+            gitlab.blob_path("main", "src/main.rs", None),
+            gitlab.commit_path("abc123"),
+            gitlab.tag_path("v1.2.3"),
+            gitlab.pulls_path().map(String::from),
+            gitlab.ci_path().map(String::from),
+            gitlab.new_issue_path().map(String::from),
+            gitlab.wiki_path().map(String::from),
+        ];
+        for path in paths {
+            let path = path.unwrap();
+            assert!(path.starts_with("/-/"), "{} lacks the /-/ prefix", path);
+        }
+    }
+
+    #[test]
+    fn test_editor_url() {
+        assert_eq!(
+            editor_url(Provider::GitHub, "https://github.com/n8henrie/git-repo.git").as_deref(),
+            Some("https://github.dev/n8henrie/git-repo")
+        );
+        assert_eq!(
+            editor_url(Provider::GitHub, "https://github.mycorp.com/team/repo"),
+            None
+        );
+        assert_eq!(
+            editor_url(Provider::GitLab, "https://gitlab.com/group/subgroup/repo").as_deref(),
+            Some("https://gitlab.com/-/ide/project/group/subgroup/repo/edit")
+        );
+        assert_eq!(
+            editor_url(Provider::Bitbucket, "https://bitbucket.org/workspace/repo"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_gist_url() {
+        assert_eq!(
+            gist_url(
+                Provider::GitHub,
+                "https://github.com/0123456789abcdef0123456789abcdef.git"
+            )
+            .as_deref(),
+            Some("https://gist.github.com/0123456789abcdef0123456789abcdef")
+        );
+        assert_eq!(
+            gist_url(Provider::GitHub, "https://github.com/n8henrie/git-repo"),
+            None
+        );
+        assert_eq!(
+            gist_url(Provider::GitHub, "https://github.com/cafe/f00d"),
+            None
+        );
+        assert_eq!(
+            gist_url(Provider::GitHub, "https://github.com/n8henrie"),
+            None
+        );
+        assert_eq!(
+            gist_url(Provider::Generic, "https://git.example.com/0123abcd"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_raw_url() {
+        assert_eq!(
+            raw_url(
+                Provider::GitHub,
+                "https://github.com/n8henrie/git-repo.git",
+                "feature/x",
+                "src/main.rs"
+            )
+            .as_deref(),
+            Some("https://raw.githubusercontent.com/n8henrie/git-repo/feature/x/src/main.rs")
+        );
+        assert_eq!(
+            raw_url(
+                Provider::GitHub,
+                "https://github.mycorp.com/team/repo",
+                "main",
+                "README.md"
+            )
+            .as_deref(),
+            Some("https://github.mycorp.com/team/repo/raw/main/README.md")
+        );
+        assert_eq!(
+            raw_url(
+                Provider::GitLab,
+                "https://gitlab.com/group/subgroup/repo",
+                "main",
+                "docs/my notes.md"
+            )
+            .as_deref(),
+            Some("https://gitlab.com/group/subgroup/repo/-/raw/main/docs/my%20notes.md")
+        );
+        assert_eq!(
+            raw_url(
+                Provider::Gitea,
+                "https://codeberg.org/owner/repo",
+                "main",
+                "a.txt"
+            )
+            .as_deref(),
+            Some("https://codeberg.org/owner/repo/raw/branch/main/a.txt")
+        );
+        assert_eq!(
+            raw_url(
+                Provider::Generic,
+                "https://git.example.com/repo",
+                "main",
+                "a.txt"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_page_url() {
+        let path = Provider::GitHub.tree_path("main").unwrap();
+        assert_eq!(
+            page_url("https://github.com/n8henrie/git-repo.git", &path),
+            "https://github.com/n8henrie/git-repo/tree/main"
+        );
+        assert_eq!(
+            page_url("https://github.com/n8henrie/git-repo", &path),
+            "https://github.com/n8henrie/git-repo/tree/main"
+        );
+    }
+
+    #[test]
+    fn test_host_map() -> Result<()> {
+        let hosts = HostMap::parse("github.mycorp.com=github, git.example.com = GitLab,")?;
+        assert_eq!(hosts.provider("github.mycorp.com"), Provider::GitHub);
+        assert_eq!(hosts.provider("git.example.com"), Provider::GitLab);
+        assert_eq!(hosts.provider("github.com"), Provider::GitHub);
+        assert_eq!(hosts.provider("git.unknown.com"), Provider::Generic);
+        assert_eq!(
+            hosts.provider("github.mycorp.com").pulls_path(),
+            Some("/pulls")
+        );
+
+        assert!(HostMap::parse("github.mycorp.com").is_err());
+        assert!(HostMap::parse("github.mycorp.com=sourcehut").is_err());
+        assert_eq!(HostMap::parse("")?, HostMap::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_host_map_extend() -> Result<()> {
+        let mut hosts = HostMap::parse("git.mycorp.com=gitlab,code.mycorp.com=github")?;
+        hosts.extend(HostMap::parse("git.mycorp.com=bitbucket")?);
+        assert_eq!(hosts.provider("git.mycorp.com"), Provider::Bitbucket);
+        assert_eq!(hosts.provider("code.mycorp.com"), Provider::GitHub);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_path() {
+        assert_eq!(
+            Provider::GitHub.tree_path("feature/x").as_deref(),
+            Some("/tree/feature/x")
+        );
+        assert_eq!(
+            Provider::GitHub.tree_path("fix the bug").as_deref(),
+            Some("/tree/fix%20the%20bug")
+        );
+        assert_eq!(
+            Provider::AzureDevOps.tree_path("feature/x").as_deref(),
+            Some("?version=GBfeature%2Fx")
+        );
+        assert_eq!(
+            Provider::GitLab.tree_path("main").as_deref(),
+            Some("/-/tree/main")
+        );
+        assert_eq!(
+            Provider::Bitbucket.tree_path("main").as_deref(),
+            Some("/src/main")
+        );
+        assert_eq!(
+            Provider::Generic.tree_path("main").as_deref(),
+            Some("/tree/main")
+        );
+    }
+
+    #[test]
+    fn test_bitbucket_paths() {
+        let base = crate::format_url("git@bitbucket.org:workspace/repo.git", true).unwrap();
+        assert_eq!(base, "https://bitbucket.org/workspace/repo");
+        let provider = provider_from_host(crate::url_host(&base).unwrap());
+        assert_eq!(provider, Provider::Bitbucket);
+        assert_eq!(provider.tree_path("main").unwrap(), "/src/main");
+        assert_eq!(provider.pulls_path().unwrap(), "/pull-requests");
+        assert_eq!(provider.commit_path("abc123").unwrap(), "/commits/abc123");
+        assert_eq!(
+            provider.blob_path("main", "README.md", None).unwrap(),
+            "/src/main/README.md"
+        );
+    }
+
+    #[test]
+    fn test_blob_path() {
+        assert_eq!(
+            Provider::GitHub
+                .blob_path("main", "src/main.rs", None)
+                .as_deref(),
+            Some("/blob/main/src/main.rs")
+        );
+        assert_eq!(
+            Provider::GitLab
+                .blob_path("main", "src/main.rs", None)
+                .as_deref(),
+            Some("/-/blob/main/src/main.rs")
+        );
+        assert_eq!(
+            Provider::Bitbucket
+                .blob_path("main", "src/main.rs", None)
+                .as_deref(),
+            Some("/src/main/src/main.rs")
+        );
+        assert_eq!(
+            Provider::AzureDevOps
+                .blob_path("main", "src/main.rs", None)
+                .as_deref(),
+            Some("?path=/src/main.rs&version=GBmain")
+        );
+        assert_eq!(
+            Provider::Generic.blob_path("main", "src/main.rs", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dir_path() {
+        assert_eq!(
+            Provider::GitHub.dir_path("main", "src/bin").as_deref(),
+            Some("/tree/main/src/bin")
+        );
+        assert_eq!(
+            Provider::GitHub.dir_path("main", "").as_deref(),
+            Some("/tree/main")
+        );
+        assert_eq!(
+            Provider::Bitbucket.dir_path("main", "src").as_deref(),
+            Some("/src/main/src")
+        );
+        assert_eq!(
+            Provider::Gitea.dir_path("main", "src").as_deref(),
+            Some("/src/branch/main/src")
+        );
+        assert_eq!(
+            Provider::AzureDevOps.dir_path("main", "src").as_deref(),
+            Some("?path=/src&version=GBmain")
+        );
+    }
+
+    #[test]
+    fn test_blob_path_encoding() {
+        assert_eq!(
+            Provider::GitHub
+                .blob_path("my branch", "docs/issue #1?.md", None)
+                .as_deref(),
+            Some("/blob/my%20branch/docs/issue%20%231%3F.md")
+        );
+        assert_eq!(
+            Provider::AzureDevOps
+                .blob_path("my branch", "docs/issue #1.md", None)
+                .as_deref(),
+            Some("?path=/docs/issue%20%231.md&version=GBmy%20branch")
+        );
+    }
+
+    #[test]
+    fn test_blob_path_lines() {
+        let line = Some(Lines {
+            start: 42,
+            end: None,
+        });
+        let range = Some(Lines {
+            start: 42,
+            end: Some(60),
+        });
+        assert_eq!(
+            Provider::GitHub
+                .blob_path("main", "src/main.rs", line)
+                .as_deref(),
+            Some("/blob/main/src/main.rs#L42")
+        );
+        assert_eq!(
+            Provider::GitHub
+                .blob_path("main", "src/main.rs", range)
+                .as_deref(),
+            Some("/blob/main/src/main.rs#L42-L60")
+        );
+        assert_eq!(
+            Provider::GitLab
+                .blob_path("main", "src/main.rs", line)
+                .as_deref(),
+            Some("/-/blob/main/src/main.rs#L42")
+        );
+        assert_eq!(
+            Provider::GitLab
+                .blob_path("main", "src/main.rs", range)
+                .as_deref(),
+            Some("/-/blob/main/src/main.rs#L42-60")
+        );
+        assert_eq!(
+            Provider::Bitbucket
+                .blob_path("main", "src/main.rs", range)
+                .as_deref(),
+            Some("/src/main/src/main.rs#lines-42:60")
+        );
+        assert_eq!(
+            Provider::AzureDevOps
+                .blob_path("main", "src/main.rs", line)
+                .as_deref(),
+            Some("?path=/src/main.rs&version=GBmain&line=42&lineEnd=43&lineStartColumn=1&lineEndColumn=1")
+        );
+        assert_eq!(
+            Provider::Generic.blob_path("main", "src/main.rs", range),
+            None
+        );
+    }
+
+    #[test]
+    fn test_commit_path() {
+        let sha = "6524fd6c3d3c5e8a4b1f0b4a8c1e2d3f4a5b6c7d";
+        assert_eq!(
+            Provider::GitHub.commit_path(sha),
+            Some(format!("/commit/{}", sha))
+        );
+        assert_eq!(
+            Provider::GitLab.commit_path(sha),
+            Some(format!("/-/commit/{}", sha))
+        );
+        assert_eq!(
+            Provider::Bitbucket.commit_path(sha),
+            Some(format!("/commits/{}", sha))
+        );
+        assert_eq!(
+            Provider::AzureDevOps.commit_path(sha),
+            Some(format!("/commit/{}", sha))
+        );
+        assert_eq!(Provider::Generic.commit_path(sha), None);
+    }
+
+    #[test]
+    fn test_tag_path() {
+        assert_eq!(
+            Provider::GitHub.tag_path("v1.2.3").as_deref(),
+            Some("/releases/tag/v1.2.3")
+        );
+        assert_eq!(
+            Provider::GitLab.tag_path("v1.2.3").as_deref(),
+            Some("/-/tags/v1.2.3")
+        );
+        assert_eq!(
+            Provider::Bitbucket.tag_path("v1.2.3").as_deref(),
+            Some("/src/v1.2.3")
+        );
+        assert_eq!(
+            Provider::AzureDevOps.tag_path("release/1.0").as_deref(),
+            Some("?version=GTrelease%2F1.0")
+        );
+        assert_eq!(Provider::Generic.tag_path("v1.2.3"), None);
+    }
+
+    #[test]
+    fn test_compare_path() {
+        assert_eq!(
+            Provider::GitHub.compare_path("main", "feature").as_deref(),
+            Some("/compare/main...feature")
+        );
+        assert_eq!(
+            Provider::GitLab.compare_path("main", "feature").as_deref(),
+            Some("/-/compare/main...feature")
+        );
+        assert_eq!(
+            Provider::Bitbucket
+                .compare_path("main", "feature")
+                .as_deref(),
+            Some("/branches/compare/feature%0Dmain")
+        );
+        assert_eq!(
+            Provider::AzureDevOps
+                .compare_path("main", "feature")
+                .as_deref(),
+            Some("/branchCompare?baseVersion=GBmain&targetVersion=GBfeature")
+        );
+        assert_eq!(Provider::Generic.compare_path("main", "feature"), None);
+    }
+
+    #[test]
+    fn test_pulls_path() {
+        assert_eq!(Provider::GitHub.pulls_path(), Some("/pulls"));
+        assert_eq!(Provider::GitLab.pulls_path(), Some("/-/merge_requests"));
+        assert_eq!(Provider::Bitbucket.pulls_path(), Some("/pull-requests"));
+        assert_eq!(Provider::AzureDevOps.pulls_path(), Some("/pullrequests"));
+        assert_eq!(Provider::Generic.pulls_path(), None);
+    }
+
+    #[test]
+    fn test_new_issue_path() {
+        assert_eq!(Provider::GitHub.new_issue_path(), Some("/issues/new"));
+        assert_eq!(Provider::GitLab.new_issue_path(), Some("/-/issues/new"));
+        assert_eq!(Provider::Bitbucket.new_issue_path(), Some("/issues/new"));
+        assert_eq!(Provider::Gitea.new_issue_path(), Some("/issues/new"));
+        assert_eq!(Provider::AzureDevOps.new_issue_path(), None);
+        assert_eq!(Provider::Generic.new_issue_path(), None);
+    }
+
+    #[test]
+    fn test_wiki_path() {
+        assert_eq!(Provider::GitHub.wiki_path(), Some("/wiki"));
+        assert_eq!(Provider::GitLab.wiki_path(), Some("/-/wikis/home"));
+        assert_eq!(Provider::Bitbucket.wiki_path(), Some("/wiki"));
+        assert_eq!(Provider::Gitea.wiki_path(), Some("/wiki"));
+        assert_eq!(Provider::AzureDevOps.wiki_path(), None);
+        assert_eq!(Provider::Generic.wiki_path(), None);
+    }
+
+    #[test]
+    fn test_settings_path() {
+        assert_eq!(Provider::GitHub.settings_path(), Some("/settings"));
+        assert_eq!(Provider::GitLab.settings_path(), Some("/-/edit"));
+        assert_eq!(Provider::Bitbucket.settings_path(), Some("/admin"));
+        assert_eq!(Provider::Gitea.settings_path(), Some("/settings"));
+        assert_eq!(Provider::AzureDevOps.settings_path(), None);
+        assert_eq!(Provider::Generic.settings_path(), None);
+    }
+
+    #[test]
+    fn test_ci_path() {
+        assert_eq!(Provider::GitHub.ci_path(), Some("/actions"));
+        assert_eq!(Provider::GitLab.ci_path(), Some("/-/pipelines"));
+        assert_eq!(Provider::Bitbucket.ci_path(), Some("/addon/pipelines/home"));
+        assert_eq!(Provider::AzureDevOps.ci_path(), None);
+        assert_eq!(Provider::Generic.ci_path(), None);
+    }
+
+    #[test]
+    fn test_gitea_paths() -> Result<()> {
+        let hosts = HostMap::parse("git.example.com=gitea")?;
+        let provider = hosts.provider("git.example.com");
+        assert_eq!(provider, Provider::Gitea);
+        assert_eq!(
+            provider.tree_path("feature/x").as_deref(),
+            Some("/src/branch/feature/x")
+        );
+        assert_eq!(
+            provider
+                .blob_path(
+                    "main",
+                    "src/main.rs",
+                    Some(Lines {
+                        start: 42,
+                        end: Some(60)
+                    })
+                )
+                .as_deref(),
+            Some("/src/branch/main/src/main.rs#L42-L60")
+        );
+        assert_eq!(
+            provider.commit_path("abc123").as_deref(),
+            Some("/commit/abc123")
+        );
+        assert_eq!(provider.pulls_path(), Some("/pulls"));
+        assert_eq!(
+            provider.tag_path("v1.2.3").as_deref(),
+            Some("/releases/tag/v1.2.3")
+        );
+        assert_eq!(provider.ci_path(), Some("/actions"));
+        Ok(())
+    }
+}
@@ -0,0 +1,954 @@
+//! Parsing `git remote --verbose` output and choosing among the remotes.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use serde::Serialize;
+
+use crate::{format_url, select_from, url_host, web_url, Error, Result, DEFAULT_MAX_ATTEMPTS};
+
+/// A named remote with the URLs it fetches from and pushes to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Remote {
+    pub name: String,
+    pub fetch_urls: Vec<String>,
+    pub push_urls: Vec<String>,
+}
+
+impl Remote {
+    /// Creates a remote that fetches from and pushes to `url`.
+    pub fn new<T: Into<String>, U: Into<String>>(name: T, url: U) -> Self {
+        let url = url.into();
+        Remote {
+            name: name.into(),
+            fetch_urls: vec![url.clone()],
+            push_urls: vec![url],
+        }
+    }
+
+    /// The URL that best represents this remote: the first fetch URL, falling
+    /// back to the first push URL.
+    pub fn url(&self) -> Option<&str> {
+        self.fetch_urls
+            .first()
+            .or_else(|| self.push_urls.first())
+            .map(String::as_str)
+    }
+
+    /// This remote with only its fetch URLs, which it then pushes to as well.
+    pub fn fetch_only(self) -> Self {
+        Remote {
+            push_urls: self.fetch_urls.clone(),
+            ..self
+        }
+    }
+
+    /// This remote with only its push URLs, which it then fetches from as
+    /// well.
+    pub fn push_only(self) -> Self {
+        Remote {
+            fetch_urls: self.push_urls.clone(),
+            ..self
+        }
+    }
+
+    /// The URLs of this remote as labeled choices. The labels only mention
+    /// fetch and push when those URLs differ, and number the URLs when there
+    /// are several of a kind, as with `git remote set-url --add --push`.
+    pub fn choices(&self) -> Vec<Choice<'_>> {
+        if self.fetch_urls == self.push_urls {
+            return self.labeled("", &self.fetch_urls).collect();
+        }
+        self.labeled("fetch", &self.fetch_urls)
+            .chain(self.labeled("push", &self.push_urls))
+            .collect()
+    }
+
+    /// Choices for `urls`, labeled with the name and `kind` of URL, if any.
+    fn labeled<'a>(
+        &'a self,
+        kind: &'a str,
+        urls: &'a [String],
+    ) -> impl Iterator<Item = Choice<'a>> + 'a {
+        urls.iter().enumerate().map(move |(index, url)| {
+            let label = match (kind, urls.len()) {
+                ("", 1) => self.name.clone(),
+                (kind, 1) => format!("{} ({})", self.name, kind),
+                ("", _) => format!("{} ({})", self.name, index + 1),
+                (kind, _) => format!("{} ({} {})", self.name, kind, index + 1),
+            };
+            Choice::new(label, &self.name, url)
+        })
+    }
+}
+
+/// A remote URL offered for selection, displayed as `label: url`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Choice<'a> {
+    pub label: String,
+    pub name: &'a str,
+    pub url: &'a str,
+}
+
+impl<'a> Choice<'a> {
+    fn new(label: String, name: &'a str, url: &'a str) -> Self {
+        Choice { label, name, url }
+    }
+}
+
+impl fmt::Display for Choice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.label, self.url)
+    }
+}
+
+/// All choices for `remotes`, without repeating a web page that several URLs
+/// convert to. A choice shared by several remotes is labeled with all of them
+/// but keeps the first one's name and URL.
+pub fn remote_choices(remotes: &[Remote]) -> Vec<Choice<'_>> {
+    let mut choices: Vec<(String, Choice)> = Vec::new();
+    for choice in remotes.iter().flat_map(Remote::choices) {
+        let page = format_url(choice.url, true).unwrap_or_else(|_| choice.url.to_owned());
+        match choices.iter_mut().find(|(url, _)| *url == page) {
+            Some((_, existing)) => {
+                if existing.name != choice.name {
+                    existing.label = format!("{}, {}", existing.label, choice.label);
+                }
+            }
+            None => choices.push((page, choice)),
+        }
+    }
+    choices.into_iter().map(|(_, choice)| choice).collect()
+}
+
+/// A remote URL and the web URL it converts to, for machine-readable output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RemoteListing<'a> {
+    pub name: &'a str,
+    pub clone_url: &'a str,
+    /// `None` if the remote URL is malformed.
+    pub web_url: Option<String>,
+}
+
+/// Lists every URL of every remote along with its web URL.
+pub fn remote_listing(remotes: &[Remote], strip_git: bool) -> Vec<RemoteListing<'_>> {
+    remotes
+        .iter()
+        .flat_map(Remote::choices)
+        .map(|choice| RemoteListing {
+            name: choice.name,
+            clone_url: choice.url,
+            web_url: format_url(choice.url, strip_git).ok(),
+        })
+        .collect()
+}
+
+/// Renders `listing` as a table with aligned name, clone URL, and web URL
+/// columns under a header, one line per row.
+pub fn listing_table(listing: &[RemoteListing<'_>]) -> String {
+    let header = ["NAME", "CLONE URL", "WEB URL"];
+    let rows: Vec<[&str; 3]> = listing
+        .iter()
+        .map(|row| {
+            [
+                row.name,
+                row.clone_url,
+                row.web_url.as_deref().unwrap_or("-"),
+            ]
+        })
+        .collect();
+    let width = |column: usize| {
+        rows.iter()
+            .chain(std::iter::once(&header))
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (name_width, clone_width) = (width(0), width(1));
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|[name, clone_url, web_url]| {
+            format!(
+                "{:<name_width$}  {:<clone_width$}  {}\n",
+                name,
+                clone_url,
+                web_url,
+                name_width = name_width,
+                clone_width = clone_width
+            )
+        })
+        .collect()
+}
+
+/// The web URLs of all of `remotes`, without repeating one that several remote
+/// URLs (e.g. SSH and HTTPS clones of the same repo) convert to.
+pub fn web_urls(remotes: &[Remote], strip_git: bool) -> Result<Vec<String>> {
+    Ok(named_web_urls(remotes, strip_git)?
+        .into_iter()
+        .map(|(_, url)| url)
+        .collect())
+}
+
+/// Like [`web_urls`], but with the name of the first remote that converts to
+/// each web URL.
+pub fn named_web_urls(remotes: &[Remote], strip_git: bool) -> Result<Vec<(&str, String)>> {
+    let mut urls: Vec<(&str, String)> = Vec::new();
+    for choice in remote_choices(remotes) {
+        let url = format_url(choice.url, strip_git)?;
+        if !urls.iter().any(|(_, existing)| *existing == url) {
+            urls.push((choice.name, url));
+        }
+    }
+    Ok(urls)
+}
+
+/// Drops the URLs of `remotes` that don't convert to a web URL, such as local
+/// paths, and the remotes left without any, warning about each on `warn`.
+pub fn retain_web_remotes<W: Write>(remotes: Vec<Remote>, warn: &mut W) -> io::Result<Vec<Remote>> {
+    let mut kept = Vec::with_capacity(remotes.len());
+    for mut remote in remotes {
+        let mut dropped: Vec<(String, Error)> = Vec::new();
+        for urls in [&mut remote.fetch_urls, &mut remote.push_urls] {
+            urls.retain(|url| match web_url(url, false) {
+                Ok(_) => true,
+                Err(e) => {
+                    if !dropped.iter().any(|(u, _)| u == url) {
+                        dropped.push((url.clone(), e));
+                    }
+                    false
+                }
+            });
+        }
+        for (url, e) in dropped {
+            writeln!(warn, "Skipping {} of remote {:?}: {}", url, remote.name, e)?;
+        }
+        if remote.url().is_some() {
+            kept.push(remote);
+        }
+    }
+    Ok(kept)
+}
+
+/// The hosts `--suggest-mirrors` suggests mirroring to, with the name of the
+/// remote to add for each.
+pub const MIRROR_HOSTS: &[(&str, &str)] = &[
+    ("github", "github.com"),
+    ("gitlab", "gitlab.com"),
+    ("bitbucket", "bitbucket.org"),
+    ("codeberg", "codeberg.org"),
+];
+
+/// The `git remote add` commands that would mirror the repo at the remote URL
+/// `url` to the same path on each of [`MIRROR_HOSTS`], except those one of
+/// `remotes` already points to or whose remote name is taken.
+pub fn mirror_commands(remotes: &[Remote], url: &str) -> Result<Vec<String>> {
+    let web = web_url(url, true)?;
+    let path = web
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split_once('/'))
+        .map(|(_, path)| path)
+        .ok_or_else(|| Error::MalformedUrl(url.to_owned()))?;
+    let configured: Vec<String> = remote_choices(remotes)
+        .iter()
+        .filter_map(|choice| web_url(choice.url, true).ok())
+        .filter_map(|web| url_host(&web).map(str::to_owned))
+        .collect();
+    Ok(MIRROR_HOSTS
+        .iter()
+        .filter(|(name, host)| {
+            !configured.iter().any(|configured| configured == host)
+                && !remotes.iter().any(|remote| remote.name == *name)
+        })
+        .map(|(name, host)| format!("git remote add {} git@{}:{}.git", name, host, path))
+        .collect())
+}
+
+/// Remotes picked automatically, in order of preference, when several exist
+/// and none was named.
+pub const DEFAULT_REMOTES: [&str; 2] = ["origin", "upstream"];
+
+/// How [`choose_remote`] picks a remote.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoteSelector<'a> {
+    /// Pick the remote with this name.
+    pub name: Option<&'a str>,
+    /// Whether the user can be prompted. If not, needing to prompt is an error.
+    pub interactive: bool,
+    /// Prompt even if one of the [`DEFAULT_REMOTES`] exists.
+    pub no_default: bool,
+}
+
+fn remote_choice(remote: &Remote) -> Option<Choice<'_>> {
+    remote
+        .url()
+        .map(|url| Choice::new(remote.name.clone(), &remote.name, url))
+}
+
+/// Finds the remote called `name`, or else the only one whose name starts
+/// with `name`.
+pub fn find_remote<'a>(remotes: &'a [Remote], name: &str) -> Result<&'a Remote> {
+    if let Some(remote) = remotes.iter().find(|remote| remote.name == name) {
+        return Ok(remote);
+    }
+    let matches: Vec<_> = remotes
+        .iter()
+        .filter(|remote| remote.name.starts_with(name))
+        .collect();
+    match matches.as_slice() {
+        [] => Err(Error::NoSuchRemote(name.to_owned())),
+        [remote] => Ok(remote),
+        _ => Err(Error::AmbiguousRemote {
+            prefix: name.to_owned(),
+            candidates: matches.iter().map(|remote| remote.name.clone()).collect(),
+        }),
+    }
+}
+
+/// Picks the remote named by `selector`. Otherwise, when there are several
+/// remote URLs, picks the first of the [`DEFAULT_REMOTES`] that exists or
+/// prompts for one.
+pub fn choose_remote<'a>(
+    remotes: &'a [Remote],
+    selector: &RemoteSelector<'_>,
+) -> Result<Choice<'a>> {
+    let stdin = io::stdin();
+    choose_remote_from(remotes, selector, &mut stdin.lock(), &mut io::stdout())
+}
+
+/// Like [`choose_remote`], but prompts with [`select_from`], reading the
+/// selection from `input` and writing the choices to `output`.
+pub fn choose_remote_from<'a, R: BufRead, W: Write + ?Sized>(
+    remotes: &'a [Remote],
+    selector: &RemoteSelector<'_>,
+    input: &mut R,
+    output: &mut W,
+) -> Result<Choice<'a>> {
+    if let Some(name) = selector.name {
+        return remote_choice(find_remote(remotes, name)?)
+            .ok_or_else(|| format!("No URL found for remote {:?}", name).into());
+    }
+    let mut choices = remote_choices(remotes);
+    if choices.len() > 1 && !selector.no_default {
+        let default = DEFAULT_REMOTES
+            .iter()
+            .find_map(|&name| remotes.iter().find(|remote| remote.name == name))
+            .and_then(remote_choice);
+        if let Some(choice) = default {
+            return Ok(choice);
+        }
+    }
+    if choices.len() <= 1 {
+        return choices.pop().ok_or(Error::NoRemotes);
+    }
+    if selector.interactive {
+        return select_from(
+            choices,
+            Some("Choose a remote by number:"),
+            DEFAULT_MAX_ATTEMPTS,
+            input,
+            output,
+        );
+    }
+    Err(Error::NeedsSelection(
+        choices.iter().map(ToString::to_string).collect(),
+    ))
+}
+
+/// Like [`choose_remote`], but only returns the URL.
+pub fn choose_remote_url<'a>(
+    remotes: &'a [Remote],
+    selector: &RemoteSelector<'_>,
+) -> Result<&'a str> {
+    choose_remote(remotes, selector).map(|choice| choice.url)
+}
+
+/// Parses `git remote get-url --all` output, which is one URL per line.
+pub fn urls_from_get_url<T: AsRef<str>>(output: T) -> Vec<String> {
+    output
+        .as_ref()
+        .lines()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Splits a line of `git remote --verbose` output into the remote's name, its
+/// URL, and whether the URL is for pushing. Any whitespace separates the
+/// fields, and whatever is between the name and the `(fetch)` or `(push)` at
+/// the end is the URL. `None` for lines without all three.
+fn parse_verbose_line(line: &str) -> Option<(&str, &str, bool)> {
+    let (rest, kind) = line.trim().rsplit_once(char::is_whitespace)?;
+    let push = match kind {
+        "(fetch)" => false,
+        "(push)" => true,
+        _ => return None,
+    };
+    let (name, url) = rest.trim_end().split_once(char::is_whitespace)?;
+    Some((name, url.trim(), push))
+}
+
+/// Parses `git remote --verbose` output into remotes, in order of appearance.
+/// Lines that don't look like `<name> <url> (fetch)` or `(push)` are skipped.
+pub fn urls_from_output<T: AsRef<str>>(output: T) -> Vec<Remote> {
+    let mut remotes: Vec<Remote> = Vec::new();
+    // `lines` drops the `\r` of Windows line endings.
+    for line in output.as_ref().lines() {
+        let (name, url, push) = match parse_verbose_line(line) {
+            Some(fields) => fields,
+            None => {
+                log::trace!("skipping `git remote --verbose` line {:?}", line);
+                continue;
+            }
+        };
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(Remote {
+                    name: name.into(),
+                    ..Remote::default()
+                });
+                remotes.len() - 1
+            }
+        };
+        let remote = &mut remotes[index];
+        let urls = if push {
+            &mut remote.push_urls
+        } else {
+            &mut remote.fetch_urls
+        };
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.into());
+        }
+    }
+    log::debug!("parsed {} remotes", remotes.len());
+    remotes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_url() -> Result<()> {
+        let remotes = vec![Remote::new("origin", "https://n8henrie.com")];
+        assert_eq!(
+            choose_remote_url(&remotes, &RemoteSelector::default())?,
+            "https://n8henrie.com"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_single_or_none() -> Result<()> {
+        let remotes = vec![Remote {
+            name: "origin".to_owned(),
+            fetch_urls: vec![],
+            push_urls: vec!["git@github.com:n8henrie/git-repo.git".to_owned()],
+        }];
+        assert_eq!(
+            choose_remote_url(&remotes, &RemoteSelector::default())?,
+            "git@github.com:n8henrie/git-repo.git"
+        );
+        let err = choose_remote_url(&[], &RemoteSelector::default()).unwrap_err();
+        assert!(matches!(err, Error::NoRemotes));
+        assert_eq!(err.to_string(), "No URL found");
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_by_name() -> Result<()> {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        assert_eq!(
+            choose_remote_url(
+                &remotes,
+                &RemoteSelector {
+                    name: Some("origin"),
+                    ..RemoteSelector::default()
+                }
+            )?,
+            "git@github.com:n8henrie/git-repo.git"
+        );
+        assert_eq!(
+            choose_remote_url(
+                &remotes,
+                &RemoteSelector {
+                    name: Some("n8henrie"),
+                    ..RemoteSelector::default()
+                }
+            )?,
+            "git@gitlab.com:n8henrie/git-repo.git"
+        );
+        let err = choose_remote_url(
+            &remotes,
+            &RemoteSelector {
+                name: Some("upstream"),
+                ..RemoteSelector::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(&err, Error::NoSuchRemote(name) if name == "upstream"));
+        assert_eq!(err.to_string(), "No remote named \"upstream\"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_by_prefix() -> Result<()> {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("fork-johndoe", "git@github.com:johndoe/git-repo.git"),
+            Remote::new("fork-janedoe", "git@github.com:janedoe/git-repo.git"),
+        ];
+        let by_name = |name| {
+            choose_remote_url(
+                &remotes,
+                &RemoteSelector {
+                    name: Some(name),
+                    ..RemoteSelector::default()
+                },
+            )
+        };
+        assert_eq!(by_name("fork-jo")?, "git@github.com:johndoe/git-repo.git");
+        assert_eq!(by_name("or")?, "git@github.com:n8henrie/git-repo.git");
+        let err = by_name("fork").unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::AmbiguousRemote { prefix, candidates }
+                if prefix == "fork" && candidates == &["fork-johndoe", "fork-janedoe"]
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Several remotes start with \"fork\": fork-johndoe, fork-janedoe"
+        );
+        assert_eq!(
+            by_name("up").unwrap_err().to_string(),
+            "No remote named \"up\""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_non_interactive() {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("fork", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        let err = choose_remote_url(&remotes, &RemoteSelector::default()).unwrap_err();
+        assert!(matches!(&err, Error::NeedsSelection(choices) if choices.len() == 2));
+        assert_eq!(
+            err.to_string(),
+            "Found several remotes but can't prompt without a terminal, choose one by name:
+  n8henrie: git@gitlab.com:n8henrie/git-repo.git
+  fork: git@github.com:n8henrie/git-repo.git"
+        );
+    }
+
+    #[test]
+    fn test_choose_url_default_origin() -> Result<()> {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("upstream", "git@github.com:upstream/git-repo.git"),
+        ];
+        assert_eq!(
+            choose_remote_url(&remotes, &RemoteSelector::default())?,
+            "git@github.com:n8henrie/git-repo.git"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_default_upstream() -> Result<()> {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("upstream", "git@github.com:upstream/git-repo.git"),
+        ];
+        assert_eq!(
+            choose_remote_url(&remotes, &RemoteSelector::default())?,
+            "git@github.com:upstream/git-repo.git"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_url_no_default() {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        let selector = RemoteSelector {
+            no_default: true,
+            ..RemoteSelector::default()
+        };
+        let err = choose_remote_url(&remotes, &selector).unwrap_err();
+        assert!(err.to_string().contains("can't prompt without a terminal"));
+    }
+
+    #[test]
+    fn test_choose_remote_from_input() -> Result<()> {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        let selector = RemoteSelector {
+            interactive: true,
+            no_default: true,
+            ..RemoteSelector::default()
+        };
+        let mut output = Vec::new();
+        let choice = choose_remote_from(
+            &remotes,
+            &selector,
+            &mut io::Cursor::new("2\n0\n"),
+            &mut output,
+        )?;
+        assert_eq!(choice.name, "n8henrie");
+        assert!(String::from_utf8_lossy(&output).contains("Choose a remote by number:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_listing_json() -> Result<()> {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        let json = serde_json::to_value(remote_listing(&remotes, true))?;
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "name": "n8henrie",
+                    "clone_url": "git@gitlab.com:n8henrie/git-repo.git",
+                    "web_url": "https://gitlab.com/n8henrie/git-repo",
+                },
+                {
+                    "name": "origin",
+                    "clone_url": "git@github.com:n8henrie/git-repo.git",
+                    "web_url": "https://github.com/n8henrie/git-repo",
+                },
+            ])
+        );
+        assert_eq!(serde_json::to_string(&remote_listing(&[], true))?, "[]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_listing_table() {
+        let remotes = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        assert_eq!(
+            listing_table(&remote_listing(&remotes, true)),
+            "\
+NAME      CLONE URL                             WEB URL
+n8henrie  git@gitlab.com:n8henrie/git-repo.git  https://gitlab.com/n8henrie/git-repo
+origin    git@github.com:n8henrie/git-repo.git  https://github.com/n8henrie/git-repo
+"
+        );
+        assert_eq!(listing_table(&[]), "NAME  CLONE URL  WEB URL\n");
+    }
+
+    #[test]
+    fn test_web_urls() -> Result<()> {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("mirror", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("https", "https://github.com/n8henrie/git-repo.git"),
+        ];
+        assert_eq!(
+            web_urls(&remotes, true)?,
+            [
+                "https://github.com/n8henrie/git-repo",
+                "https://gitlab.com/n8henrie/git-repo",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_commands() -> Result<()> {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("codeberg", "https://git.example.com/n8henrie/git-repo"),
+        ];
+        assert_eq!(
+            mirror_commands(&remotes, "git@github.com:n8henrie/git-repo.git")?,
+            [
+                "git remote add gitlab git@gitlab.com:n8henrie/git-repo.git",
+                "git remote add bitbucket git@bitbucket.org:n8henrie/git-repo.git",
+            ]
+        );
+        assert!(mirror_commands(&remotes, "/srv/git/repo.git").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls_from_get_url() {
+        assert_eq!(
+            urls_from_get_url(
+                "git@github.com:n8henrie/git-repo.git\nhttps://gitlab.com/n8henrie/git-repo.git\n\n"
+            ),
+            [
+                "git@github.com:n8henrie/git-repo.git",
+                "https://gitlab.com/n8henrie/git-repo.git",
+            ]
+        );
+        assert!(urls_from_get_url("").is_empty());
+    }
+
+    #[test]
+    fn test_retain_web_remotes() -> Result<()> {
+        let remotes = vec![
+            Remote::new("local", "/srv/git/repo.git"),
+            Remote {
+                name: "origin".into(),
+                fetch_urls: vec!["git@github.com:n8henrie/git-repo.git".into()],
+                push_urls: vec!["file:///srv/git/repo.git".into()],
+            },
+        ];
+        let mut warnings = Vec::new();
+        let remotes = retain_web_remotes(remotes, &mut warnings)?;
+        assert_eq!(
+            remotes,
+            [Remote {
+                name: "origin".into(),
+                fetch_urls: vec!["git@github.com:n8henrie/git-repo.git".into()],
+                push_urls: vec![],
+            }]
+        );
+        let warnings = String::from_utf8_lossy(&warnings);
+        assert!(warnings.contains("/srv/git/repo.git of remote \"local\""));
+        assert!(warnings.contains("file:///srv/git/repo.git of remote \"origin\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls_from_output() {
+        let input = "n8henrie        git@gitlab.com:n8henrie/git-repo.git (fetch)
+n8henrie        git@gitlab.com:n8henrie/git-repo.git (push)
+origin  git@github.com:n8henrie/git-repo.git (fetch)
+origin  git@github.com:n8henrie/git-repo.git (push)";
+        let output = vec![
+            Remote::new("n8henrie", "git@gitlab.com:n8henrie/git-repo.git"),
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        assert_eq!(urls_from_output(input), output)
+    }
+
+    #[test]
+    fn test_urls_from_output_crlf() {
+        let input = "origin\tgit@github.com:n8henrie/git-repo.git (fetch)\r\n\
+                     origin\tgit@github.com:n8henrie/git-repo.git (push)\r\n\
+                     \r\n\
+                     upstream \t https://gitlab.com/n8henrie/git-repo.git\t(fetch) \r\n";
+        assert_eq!(
+            urls_from_output(input),
+            [
+                Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+                Remote {
+                    name: "upstream".into(),
+                    fetch_urls: vec!["https://gitlab.com/n8henrie/git-repo.git".into()],
+                    push_urls: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            urls_from_get_url("git@github.com:n8henrie/git-repo.git\r\n"),
+            ["git@github.com:n8henrie/git-repo.git"]
+        );
+    }
+
+    #[test]
+    fn test_urls_from_output_malformed() {
+        let input = "origin\tgit@github.com:n8henrie/git-repo.git (fetch)
+lonely
+upstream
+local\t/srv/git/my repo.git (push)
+stray https://example.com/not/a/remote
+";
+        assert_eq!(
+            urls_from_output(input),
+            [
+                Remote {
+                    name: "origin".into(),
+                    fetch_urls: vec!["git@github.com:n8henrie/git-repo.git".into()],
+                    push_urls: vec![],
+                },
+                Remote {
+                    name: "local".into(),
+                    fetch_urls: vec![],
+                    push_urls: vec!["/srv/git/my repo.git".into()],
+                },
+            ]
+        );
+        assert_eq!(urls_from_output("lonely"), []);
+    }
+
+    #[test]
+    fn test_several_push_urls() -> Result<()> {
+        let input = "origin\tgit@github.com:n8henrie/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+origin\tgit@gitlab.com:n8henrie/git-repo.git (push)
+";
+        let remotes = urls_from_output(input);
+        assert_eq!(remotes[0].push_urls.len(), 2);
+        let labels: Vec<String> = remote_choices(&remotes)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "origin (fetch): git@github.com:n8henrie/git-repo.git",
+                "origin (push 2): git@gitlab.com:n8henrie/git-repo.git",
+            ]
+        );
+        assert_eq!(
+            web_urls(&remotes, true)?,
+            [
+                "https://github.com/n8henrie/git-repo",
+                "https://gitlab.com/n8henrie/git-repo",
+            ]
+        );
+
+        let mirrored = Remote {
+            name: "mirror".into(),
+            fetch_urls: vec![
+                "git@github.com:n8henrie/git-repo.git".into(),
+                "git@gitlab.com:n8henrie/git-repo.git".into(),
+            ],
+            push_urls: vec![
+                "git@github.com:n8henrie/git-repo.git".into(),
+                "git@gitlab.com:n8henrie/git-repo.git".into(),
+            ],
+        };
+        let labels: Vec<String> = mirrored.choices().iter().map(|c| c.label.clone()).collect();
+        assert_eq!(labels, ["mirror (1)", "mirror (2)"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diverging_fetch_and_push() {
+        let input = "origin\thttps://github.com/upstream/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+mirror\tgit@gitlab.com:n8henrie/git-repo.git (fetch)
+mirror\tgit@gitlab.com:n8henrie/git-repo.git (push)";
+        let remotes = urls_from_output(input);
+        assert_eq!(
+            remotes[0],
+            Remote {
+                name: "origin".into(),
+                fetch_urls: vec!["https://github.com/upstream/git-repo.git".into()],
+                push_urls: vec!["git@github.com:n8henrie/git-repo.git".into()],
+            }
+        );
+        let choices: Vec<String> = remote_choices(&remotes)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            choices,
+            [
+                "origin (fetch): https://github.com/upstream/git-repo.git",
+                "origin (push): git@github.com:n8henrie/git-repo.git",
+                "mirror: git@gitlab.com:n8henrie/git-repo.git",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_and_push_only() -> Result<()> {
+        let input = "origin\thttps://github.com/upstream/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+mirror\tgit@gitlab.com:n8henrie/git-repo.git (fetch)
+mirror\tgit@gitlab.com:n8henrie/git-repo.git (push)";
+        let selector = RemoteSelector {
+            name: Some("origin"),
+            ..RemoteSelector::default()
+        };
+
+        let remotes: Vec<Remote> = urls_from_output(input)
+            .into_iter()
+            .map(Remote::fetch_only)
+            .collect();
+        assert_eq!(
+            choose_remote(&remotes, &selector)?.url,
+            "https://github.com/upstream/git-repo.git"
+        );
+        assert_eq!(remote_choices(&remotes).len(), 2);
+
+        let remotes: Vec<Remote> = urls_from_output(input)
+            .into_iter()
+            .map(Remote::push_only)
+            .collect();
+        assert_eq!(
+            choose_remote(&remotes, &selector)?.url,
+            "git@github.com:n8henrie/git-repo.git"
+        );
+        assert_eq!(
+            remotes[1],
+            Remote::new("mirror", "git@gitlab.com:n8henrie/git-repo.git")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_choices_shared_url() {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("n8henrie", "git@github.com:n8henrie/git-repo.git"),
+        ];
+        assert_eq!(
+            remote_choices(&remotes),
+            [Choice::new(
+                "origin, n8henrie".into(),
+                "origin",
+                "git@github.com:n8henrie/git-repo.git"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_remote_choices_same_web_url() {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("https", "https://github.com/n8henrie/git-repo"),
+        ];
+        assert_eq!(
+            remote_choices(&remotes),
+            [Choice::new(
+                "origin, https".into(),
+                "origin",
+                "git@github.com:n8henrie/git-repo.git"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_rendered_choices() -> Result<()> {
+        let remotes = vec![
+            Remote::new("origin", "git@github.com:n8henrie/git-repo.git"),
+            Remote::new("upstream", "git@github.com:upstream/git-repo.git"),
+        ];
+        let rendered: Vec<String> = remote_choices(&remotes)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            rendered,
+            [
+                "origin: git@github.com:n8henrie/git-repo.git",
+                "upstream: git@github.com:upstream/git-repo.git",
+            ]
+        );
+
+        let choice = crate::select_from_reader(remote_choices(&remotes), &mut &b"1\n"[..])?;
+        assert_eq!(choice.url, "git@github.com:upstream/git-repo.git");
+        Ok(())
+    }
+}
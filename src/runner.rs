@@ -0,0 +1,101 @@
+//! Running external commands, behind a trait so tests can fake them.
+
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::process::{Command, Output};
+
+use crate::{Launch, Result};
+
+/// Runs the git and browser commands the crate needs.
+pub trait CommandRunner {
+    /// Runs `program` with `args` to completion and captures its output.
+    fn run(&self, program: &OsStr, args: &[OsString]) -> io::Result<Output>;
+
+    /// Starts `cmd`, waiting for it as `how` says.
+    fn launch(&self, cmd: Command, how: Launch) -> Result<()>;
+
+    /// Whether `program` is installed, i.e. on `PATH`.
+    fn on_path(&self, program: &str) -> bool {
+        crate::on_path(program)
+    }
+}
+
+/// Runs commands for real.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &OsStr, args: &[OsString]) -> io::Result<Output> {
+        Command::new(program).args(args).output()
+    }
+
+    fn launch(&self, cmd: Command, how: Launch) -> Result<()> {
+        crate::launch(cmd, how)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{output, MockRunner};
+    use super::*;
+
+    use crate::{
+        choose_remote, format_url, git_output_with, open_url_with, retain_web_remotes,
+        urls_from_output, Error, RemoteSelector,
+    };
+
+    const REMOTES: &str = "\
+origin\tgit@github.com:n8henrie/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+local\t/srv/git/repo.git (fetch)
+local\t/srv/git/repo.git (push)
+";
+
+    #[test]
+    fn test_pipeline_with_mock_runner() -> Result<()> {
+        let runner = MockRunner::new(REMOTES);
+        let output = git_output_with(&runner, None)?;
+        assert_eq!(
+            runner.runs.borrow().last().unwrap()[..],
+            ["remote", "--verbose"]
+        );
+
+        let remotes = retain_web_remotes(urls_from_output(output), &mut io::sink())?;
+        let choice = choose_remote(&remotes, &RemoteSelector::default())?;
+        assert_eq!(choice.name, "origin");
+        let url = format_url(choice.url, true)?;
+        assert_eq!(url, "https://github.com/n8henrie/git-repo");
+
+        open_url_with(&runner, &url, Launch::Detach)?;
+        assert_eq!(
+            runner.launches.borrow()[0].last().map(OsString::as_os_str),
+            Some(OsStr::new("https://github.com/n8henrie/git-repo"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_warning_without_remotes() {
+        let runner = MockRunner::replying(|_| {
+            output(
+                "",
+                "fatal: detected dubious ownership in repository at '/srv/repo'\n",
+            )
+        });
+        let err = git_output_with(&runner, None).unwrap_err();
+        assert!(matches!(err, Error::GitWarning(_)));
+        assert!(err
+            .to_string()
+            .contains("detected dubious ownership in repository"));
+
+        let runner = MockRunner::replying(|_| output(REMOTES, "warning: something harmless\n"));
+        assert!(git_output_with(&runner, None).is_ok());
+        assert_eq!(
+            git_output_with(&MockRunner::new(""), None).ok().as_deref(),
+            Some("")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! A [`CommandRunner`] for tests that answers git with canned output and
+//! records what it was asked to run instead of running it.
+//!
+//! Both the library's and the binary's tests include this file, and each uses
+//! only part of it.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::ffi::{OsStr, OsString};
+use std::io;
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+#[cfg(windows)]
+use std::os::windows::process::ExitStatusExt;
+use std::process::{Command, ExitStatus, Output};
+
+use crate::{CommandRunner, Error, Launch, Result};
+
+/// A successful run that printed `stdout` and `stderr`.
+pub fn output(stdout: &str, stderr: &str) -> io::Result<Output> {
+    Ok(Output {
+        status: ExitStatus::from_raw(0),
+        stdout: stdout.as_bytes().to_vec(),
+        stderr: stderr.as_bytes().to_vec(),
+    })
+}
+
+/// A run that exited with status 1 after printing `stderr`.
+pub fn failure(stderr: &str) -> io::Result<Output> {
+    Ok(Output {
+        // A raw wait status, whose exit code is in the high byte on Unix.
+        status: ExitStatus::from_raw(if cfg!(unix) { 1 << 8 } else { 1 }),
+        stdout: Vec::new(),
+        stderr: stderr.as_bytes().to_vec(),
+    })
+}
+
+/// Makes the output of a git command from its arguments.
+type Reply = Box<dyn Fn(&[OsString]) -> io::Result<Output>>;
+
+/// Answers each git command with what `reply` returns for its arguments,
+/// which include any `-C <dir>`. `on_path` only finds `xdg-open`.
+pub struct MockRunner {
+    reply: Reply,
+    /// The arguments of each git command run, in order.
+    pub runs: RefCell<Vec<Vec<OsString>>>,
+    /// The program and arguments of each browser command launched.
+    pub launches: RefCell<Vec<Vec<OsString>>>,
+    /// Whether the browser command exits with an error.
+    pub browser_fails: bool,
+}
+
+impl MockRunner {
+    /// Answers every git command with `stdout`.
+    pub fn new(stdout: &'static str) -> Self {
+        MockRunner::replying(move |_| output(stdout, ""))
+    }
+
+    /// Answers git commands with `reply`.
+    pub fn replying(reply: impl Fn(&[OsString]) -> io::Result<Output> + 'static) -> Self {
+        MockRunner {
+            reply: Box::new(reply),
+            runs: RefCell::default(),
+            launches: RefCell::default(),
+            browser_fails: false,
+        }
+    }
+
+    /// How many git commands were run.
+    pub fn run_count(&self) -> usize {
+        self.runs.borrow().len()
+    }
+}
+
+impl CommandRunner for MockRunner {
+    fn run(&self, _program: &OsStr, args: &[OsString]) -> io::Result<Output> {
+        self.runs.borrow_mut().push(args.to_vec());
+        (self.reply)(args)
+    }
+
+    fn launch(&self, cmd: Command, _how: Launch) -> Result<()> {
+        let mut argv = vec![cmd.get_program().to_owned()];
+        argv.extend(cmd.get_args().map(ToOwned::to_owned));
+        self.launches.borrow_mut().push(argv);
+        if self.browser_fails {
+            return Err(Error::BrowserFailed {
+                program: cmd.get_program().to_string_lossy().into_owned(),
+                code: Some(1),
+            });
+        }
+        Ok(())
+    }
+
+    fn on_path(&self, program: &str) -> bool {
+        program == "xdg-open"
+    }
+}
@@ -0,0 +1,205 @@
+//! Expanding `Host` aliases from `~/.ssh/config` in remote URLs.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// The `HostName` of each `Host` alias in an ssh config file. Only aliases
+/// named exactly are known; patterns with wildcards or negations are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SshConfig(HashMap<String, String>);
+
+/// The user's ssh config file given the value of `$HOME`, or `None` if it
+/// isn't usable.
+pub fn ssh_config_path(home: Option<OsString>) -> Option<PathBuf> {
+    home.filter(|home| !home.is_empty())
+        .map(|home| Path::new(&home).join(".ssh").join("config"))
+}
+
+impl SshConfig {
+    /// Loads the file from [`ssh_config_path`], or no aliases if there is no
+    /// such file.
+    pub fn load() -> Result<Self> {
+        match ssh_config_path(std::env::var_os("HOME")) {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the ssh config file at `path`, or no aliases if it doesn't exist.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(Self::parse(&contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Couldn't read {}: {}", path.display(), e).into()),
+        }
+    }
+
+    /// Parses the contents of an ssh config file. As with ssh, the first
+    /// `HostName` given for an alias wins, and `%h` in it stands for the alias.
+    pub fn parse(contents: &str) -> Self {
+        let mut hosts = HashMap::new();
+        let mut aliases: Vec<String> = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+                Some((keyword, value)) => (keyword, value.trim_start_matches(['=', ' ', '\t'])),
+                None => continue,
+            };
+            match keyword.to_ascii_lowercase().as_str() {
+                "host" => {
+                    aliases = value
+                        .split_whitespace()
+                        .filter(|alias| !alias.contains(['*', '?', '!']))
+                        .map(str::to_ascii_lowercase)
+                        .collect()
+                }
+                "match" => aliases.clear(),
+                "hostname" => {
+                    let hostname = value.trim().trim_matches('"');
+                    for alias in &aliases {
+                        hosts
+                            .entry(alias.clone())
+                            .or_insert_with(|| hostname.replace("%h", alias));
+                    }
+                }
+                _ => {}
+            }
+        }
+        SshConfig(hosts)
+    }
+
+    /// The real host name of `host`, if it's an alias.
+    pub fn hostname(&self, host: &str) -> Option<&str> {
+        self.0.get(&host.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    /// `url` with its host replaced by the real host name if it's an ssh URL,
+    /// like `git@work-github:user/repo.git` or `ssh://work-github/user/repo`,
+    /// whose host is an alias. Other URLs are returned unchanged.
+    pub fn expand(&self, url: &str) -> String {
+        let (prefix, authority, rest) = match url.split_once("://") {
+            Some((scheme, after)) if scheme.eq_ignore_ascii_case("ssh") => {
+                let (authority, path) = after.split_at(after.find('/').unwrap_or(after.len()));
+                (&url[..scheme.len() + 3], authority, path)
+            }
+            Some(_) => return url.to_owned(),
+            None => match url.split_once(':') {
+                Some((authority, _)) if !authority.contains('/') => {
+                    (&url[..0], authority, &url[authority.len()..])
+                }
+                _ => return url.to_owned(),
+            },
+        };
+        let (user, host_and_port) = match authority.rsplit_once('@') {
+            Some((user, host)) => (&authority[..user.len() + 1], host),
+            None => ("", authority),
+        };
+        let (host, port) = if prefix.is_empty() || host_and_port.starts_with('[') {
+            (host_and_port, "")
+        } else {
+            host_and_port.split_at(host_and_port.find(':').unwrap_or(host_and_port.len()))
+        };
+        match self.hostname(host) {
+            Some(hostname) => format!("{}{}{}{}{}", prefix, user, hostname, port, rest),
+            None => url.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# Work account
+Host work-github
+    HostName github.com
+    User git
+    IdentityFile ~/.ssh/work
+
+Host *.internal !bastion.internal alt-gitlab Other
+  Hostname=gitlab.%h.example.com
+
+Host other
+    HostName ignored.example.com
+
+Match host foo
+    HostName match.example.com
+";
+
+    #[test]
+    fn test_ssh_config_path() {
+        assert_eq!(
+            ssh_config_path(Some("/home/me".into())),
+            Some(PathBuf::from("/home/me/.ssh/config"))
+        );
+        assert_eq!(ssh_config_path(Some("".into())), None);
+        assert_eq!(ssh_config_path(None), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = SshConfig::parse(CONFIG);
+        assert_eq!(config.hostname("work-github"), Some("github.com"));
+        assert_eq!(
+            config.hostname("alt-gitlab"),
+            Some("gitlab.alt-gitlab.example.com")
+        );
+        assert_eq!(config.hostname("OTHER"), Some("gitlab.other.example.com"));
+        assert_eq!(config.hostname("bastion.internal"), None);
+        assert_eq!(config.hostname("github.com"), None);
+        assert_eq!(SshConfig::parse(""), SshConfig::default());
+    }
+
+    #[test]
+    fn test_expand() {
+        let config = SshConfig::parse(CONFIG);
+        for (url, expanded) in [
+            (
+                "git@work-github:n8henrie/git-repo.git",
+                "git@github.com:n8henrie/git-repo.git",
+            ),
+            (
+                "work-github:n8henrie/git-repo",
+                "github.com:n8henrie/git-repo",
+            ),
+            (
+                "ssh://git@work-github:2222/n8henrie/git-repo.git",
+                "ssh://git@github.com:2222/n8henrie/git-repo.git",
+            ),
+            (
+                "ssh://work-github/n8henrie/git-repo",
+                "ssh://github.com/n8henrie/git-repo",
+            ),
+            (
+                "https://work-github/n8henrie/git-repo",
+                "https://work-github/n8henrie/git-repo",
+            ),
+            (
+                "git@github.com:n8henrie/git-repo.git",
+                "git@github.com:n8henrie/git-repo.git",
+            ),
+            ("/srv/git/repo.git", "/srv/git/repo.git"),
+        ] {
+            assert_eq!(config.expand(url), expanded);
+        }
+    }
+
+    #[test]
+    fn test_load_missing() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(
+            SshConfig::load_from(&dir.path().join("config"))?,
+            SshConfig::default()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! Filling in URL templates given with `--template`.
+
+use crate::{Error, Result};
+
+/// The placeholders a template may use, written as `{host}` and so on.
+pub const PLACEHOLDERS: &[&str] = &["host", "path", "branch", "sha", "file", "line"];
+
+/// Fills in the placeholders of `template`, such as
+/// `https://{host}/{path}/tree/{branch}`, with `value(name)`, which is only
+/// asked for the placeholders the template uses. `{{` and `}}` stand for
+/// literal braces. Placeholders not in [`PLACEHOLDERS`] and unclosed braces
+/// are errors.
+pub fn render_template(
+    template: &str,
+    mut value: impl FnMut(&str) -> Result<String>,
+) -> Result<String> {
+    let invalid = |why: &str| Error::Other(format!("Invalid template {:?}: {}", template, why));
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if rest[start..].starts_with("{{") || rest[start..].starts_with("}}") {
+            rendered.push_str(&rest[start..=start]);
+            rest = &after[1..];
+            continue;
+        }
+        if rest[start..].starts_with('}') {
+            return Err(invalid("unmatched `}`, write `}}` for a literal one"));
+        }
+        let end = after
+            .find('}')
+            .ok_or_else(|| invalid("unclosed `{`, write `{{` for a literal one"))?;
+        let name = &after[..end];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(invalid(&format!(
+                "unknown placeholder {{{}}}, expected one of {}",
+                name,
+                PLACEHOLDERS
+                    .iter()
+                    .map(|name| format!("{{{}}}", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        rendered.push_str(&value(name)?);
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(name: &str) -> Result<String> {
+        Ok(match name {
+            "host" => "git.example.com",
+            "path" => "group/repo",
+            "branch" => "main",
+            "sha" => "0123abcd",
+            "file" => "src/lib.rs",
+            "line" => "42",
+            _ => unreachable!("asked for {}", name),
+        }
+        .to_owned())
+    }
+
+    #[test]
+    fn test_render_template() -> Result<()> {
+        assert_eq!(
+            render_template("https://{host}/{path}/tree/{branch}", known)?,
+            "https://git.example.com/group/repo/tree/main"
+        );
+        assert_eq!(
+            render_template("https://{host}/{path}/blob/{sha}/{file}#L{line}", known)?,
+            "https://git.example.com/group/repo/blob/0123abcd/src/lib.rs#L42"
+        );
+        assert_eq!(
+            render_template("https://{host}/x?q={{literal}}", known)?,
+            "https://git.example.com/x?q={literal}"
+        );
+        assert_eq!(
+            render_template("no placeholders", known)?,
+            "no placeholders"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_template_only_asks_for_used_values() -> Result<()> {
+        let mut asked = Vec::new();
+        render_template("{host}/{path}", |name| {
+            asked.push(name.to_owned());
+            known(name)
+        })?;
+        assert_eq!(asked, ["host", "path"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let err = render_template("https://{host}/{repo}", known).unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder {repo}"));
+        assert!(err.to_string().contains("{host}, {path}"));
+        assert!(render_template("https://{host", known).is_err());
+        assert!(render_template("https://host}", known).is_err());
+        assert!(render_template("{branch}", |_| Err("No branch".into())).is_err());
+    }
+}
@@ -0,0 +1,1354 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git").args(args).current_dir(dir).status()?;
+    assert!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+fn repo_with_remote(url: &str) -> Result<tempfile::TempDir> {
+    let dir = tempfile::tempdir()?;
+    git(dir.path(), &["init", "--quiet"])?;
+    git(dir.path(), &["remote", "add", "origin", url])?;
+    Ok(dir)
+}
+
+fn git_repo(dir: &Path, args: &[&str]) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .args(args)
+        .current_dir(dir)
+        // Any attempt to launch a browser fails loudly.
+        .env("BROWSER", "/nonexistent/browser")
+        // Ignore the configuration of whoever runs the tests.
+        .env("XDG_CONFIG_HOME", dir.join(".config"))
+        .env_remove("GIT_REPO_HOST_MAP")
+        .env_remove("GIT_REPO_ACTION")
+        .env_remove("GIT_REPO_LOG")
+        .output()?)
+}
+
+#[test]
+fn test_print_does_not_open_browser() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    for flag in &["--print", "-p"] {
+        let output = git_repo(dir.path(), &[flag])?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8(output.stdout)?,
+            "https://github.com/n8henrie/git-repo\n"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_open_is_default() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    for args in &[&[][..], &["--open"]] {
+        let output = git_repo(dir.path(), args)?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("/nonexistent/browser"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_conflicting_actions() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    for args in &[
+        &["--print", "--copy"][..],
+        &["--open", "--print"],
+        &["--open", "--no-open"],
+        &["--copy", "--interactive-action"],
+    ] {
+        let output = git_repo(dir.path(), args)?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("cannot be used with"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_print_keep_git() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--keep-git"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo.git\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_remote_without_path() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:")?;
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("has no repository path"));
+    Ok(())
+}
+
+#[test]
+fn test_skips_local_remote() -> Result<()> {
+    let dir = repo_with_remote("/srv/git/repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "github",
+            "git@github.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+    assert!(String::from_utf8(output.stderr)?.contains("Skipping /srv/git/repo.git"));
+    Ok(())
+}
+
+#[test]
+fn test_remote_by_name() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "n8henrie",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print", "--remote", "n8henrie"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/n8henrie/git-repo\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "--remote", "upstream"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("No remote named"));
+    Ok(())
+}
+
+#[test]
+fn test_print_branch() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--branch", "n8henrie/feature-x"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/tree/n8henrie/feature-x\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_print_upstream_branch() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "-c",
+            "user.name=git-repo",
+            "-c",
+            "user.email=git-repo@example.com",
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "init",
+        ],
+    )?;
+    git(dir.path(), &["checkout", "--quiet", "-b", "wip"])?;
+    let output = git_repo(dir.path(), &["--print", "--branch"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/tree/wip\n"
+    );
+
+    git(
+        dir.path(),
+        &["update-ref", "refs/remotes/origin/main", "HEAD"],
+    )?;
+    git(
+        dir.path(),
+        &["branch", "--quiet", "--set-upstream-to", "origin/main"],
+    )?;
+    let output = git_repo(dir.path(), &["--print", "--branch"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/tree/main\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_outside_git_repo() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .arg("--print")
+        .current_dir(dir.path())
+        .env("GIT_CEILING_DIRECTORIES", dir.path().parent().unwrap())
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("`git remote --verbose` failed"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("not a git repository"), "{}", stderr);
+    Ok(())
+}
+
+#[test]
+fn test_path_option() -> Result<()> {
+    let repo = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let elsewhere = tempfile::tempdir()?;
+    let output = git_repo(
+        elsewhere.path(),
+        &["--print", "-C", repo.path().to_str().unwrap()],
+    )?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let missing = elsewhere.path().join("missing");
+    let output = git_repo(
+        elsewhere.path(),
+        &["--print", "-C", missing.to_str().unwrap()],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("does not exist"));
+    Ok(())
+}
+
+#[test]
+fn test_git_bin_override() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .arg("--print")
+        .current_dir(dir.path())
+        .env("GIT_REPO_GIT_BIN", "/nonexistent/git")
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("`/nonexistent/git` not found"));
+    Ok(())
+}
+
+#[test]
+fn test_verbose() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert!(output.stderr.is_empty());
+
+    let output = git_repo(dir.path(), &["--print", "--verbose"])?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("origin\tgit@github.com:n8henrie/git-repo.git (fetch)"));
+    assert!(stderr.contains("Chosen URL: git@github.com:n8henrie/git-repo.git"));
+    assert!(stderr.contains("Formatted URL: https://github.com/n8henrie/git-repo"));
+    Ok(())
+}
+
+#[test]
+fn test_print_file() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    std::fs::create_dir(dir.path().join("src"))?;
+    std::fs::write(dir.path().join("src").join("main.rs"), "")?;
+    let output = git_repo(dir.path(), &["--print", "src/main.rs"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/blob/main/src/main.rs\n"
+    );
+
+    let output = git_repo(
+        &dir.path().join("src"),
+        &["--print", "--branch", "dev", "main.rs"],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/blob/dev/src/main.rs\n"
+    );
+
+    let outside = tempfile::tempdir()?;
+    std::fs::write(outside.path().join("other.rs"), "")?;
+    let output = git_repo(
+        dir.path(),
+        &["--print", outside.path().join("other.rs").to_str().unwrap()],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("is outside the repository"));
+    Ok(())
+}
+
+#[test]
+fn test_print_file_from_nested_directory() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let nested = dir.path().join("src").join("a").join("b");
+    std::fs::create_dir_all(&nested)?;
+    std::fs::write(nested.join("mod.rs"), "")?;
+    std::fs::write(dir.path().join("src").join("lib.rs"), "")?;
+    for (file, expected) in [("mod.rs", "src/a/b/mod.rs"), ("../../lib.rs", "src/lib.rs")] {
+        let output = git_repo(&nested, &["--print", file])?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8(output.stdout)?,
+            format!(
+                "https://github.com/n8henrie/git-repo/blob/main/{}\n",
+                expected
+            )
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_print_directory() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    std::fs::create_dir_all(dir.path().join("src").join("bin"))?;
+    std::fs::write(dir.path().join("src").join("main.rs"), "")?;
+    let print = |args: &[&str]| -> Result<String> {
+        let output = git_repo(&dir.path().join("src"), args)?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8(output.stdout)?)
+    };
+    assert_eq!(
+        print(&["--print", "bin"])?,
+        "https://github.com/n8henrie/git-repo/tree/main/src/bin\n"
+    );
+    assert_eq!(
+        print(&["--print", "main.rs"])?,
+        "https://github.com/n8henrie/git-repo/blob/main/src/main.rs\n"
+    );
+    assert_eq!(
+        print(&["--print", "--path-style", "blob", "bin"])?,
+        "https://github.com/n8henrie/git-repo/blob/main/src/bin\n"
+    );
+    assert_eq!(
+        print(&["--print", "."])?,
+        "https://github.com/n8henrie/git-repo/tree/main/src\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "missing.rs"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("missing.rs does not exist"));
+    Ok(())
+}
+
+#[test]
+fn test_print_file_lines() -> Result<()> {
+    let dir = repo_with_remote("git@gitlab.com:n8henrie/git-repo.git")?;
+    std::fs::write(dir.path().join("main.rs"), "")?;
+    let output = git_repo(dir.path(), &["--print", "main.rs:42-60"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/n8henrie/git-repo/-/blob/main/main.rs#L42-60\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "main.rs:0"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("Invalid line number"));
+    Ok(())
+}
+
+#[test]
+fn test_print_commit() -> Result<()> {
+    let dir = repo_with_remote("git@gitlab.com:n8henrie/git-repo.git")?;
+    let commit = |message: &str| {
+        git(
+            dir.path(),
+            &[
+                "-c",
+                "user.name=git-repo",
+                "-c",
+                "user.email=git-repo@example.com",
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+        )
+    };
+    commit("first")?;
+    commit("second")?;
+    let sha = |rev: &str| -> Result<String> {
+        let output = Command::new("git")
+            .args(["rev-parse", rev])
+            .current_dir(dir.path())
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+    };
+
+    let output = git_repo(dir.path(), &["--print", "--commit"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!(
+            "https://gitlab.com/n8henrie/git-repo/-/commit/{}\n",
+            sha("HEAD")?
+        )
+    );
+    let output = git_repo(dir.path(), &["--print", "--commit", "HEAD~1"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!(
+            "https://gitlab.com/n8henrie/git-repo/-/commit/{}\n",
+            sha("HEAD~1")?
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_print_tag() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "-c",
+            "user.name=git-repo",
+            "-c",
+            "user.email=git-repo@example.com",
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "init",
+        ],
+    )?;
+    git(dir.path(), &["tag", "v1.2.3"])?;
+    let output = git_repo(dir.path(), &["--print", "--tag", "v1.2.3"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/releases/tag/v1.2.3\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "--tag", "v9.9.9"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("git fetch --tags"));
+    Ok(())
+}
+
+#[test]
+fn test_several_remotes_without_terminal() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "n8henrie",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "--no-default"])?;
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("can't prompt without a terminal"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("n8henrie: git@gitlab.com:n8henrie/git-repo.git"));
+    Ok(())
+}
+
+#[test]
+fn test_all() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "mirror",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "https",
+            "https://github.com/n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--all", "--print"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\nhttps://gitlab.com/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_get_url() -> Result<()> {
+    let dir = repo_with_remote("gh:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &["config", "url.git@github.com:.insteadOf", "gh:"],
+    )?;
+    let output = git_repo(dir.path(), &["--print", "--get-url"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_url() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let output = git_repo(
+        dir.path(),
+        &["--print", "--url", "git@github.com:n8henrie/git-repo.git"],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .args(["--print", "--pulls", "--url", "-"])
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"git@gitlab.com:n8henrie/git-repo.git\n")?;
+    let output = child.wait_with_output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/n8henrie/git-repo/-/merge_requests\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_json() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    git(dir.path(), &["init", "--quiet"])?;
+    let output = git_repo(dir.path(), &["--json"])?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, "[]\n");
+
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            "git@github.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--json"])?;
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json[0]["web_url"], "https://github.com/n8henrie/git-repo");
+    Ok(())
+}
+
+#[test]
+fn test_completions() -> Result<()> {
+    // Run outside of any repository, since completions shouldn't need git.
+    let dir = tempfile::tempdir()?;
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .args(["--completions", "bash"])
+        .current_dir(dir.path())
+        .env("GIT_CEILING_DIRECTORIES", dir.path().parent().unwrap())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(!stdout.is_empty());
+    assert!(stdout.contains("git-repo"));
+    Ok(())
+}
+
+#[test]
+fn test_config_file() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "n8henrie",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let config_dir = dir.path().join(".config").join("git-repo");
+    std::fs::create_dir_all(&config_dir)?;
+    std::fs::write(
+        config_dir.join("config.toml"),
+        r#"
+default_action = "print"
+default_remote = "n8henrie"
+strip_git = false
+"#,
+    )?;
+
+    let output = git_repo(dir.path(), &[])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/n8henrie/git-repo.git\n"
+    );
+
+    let output = git_repo(dir.path(), &["--remote", "origin", "--strip-git"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_new_issue() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let output = git_repo(
+        dir.path(),
+        &[
+            "--print",
+            "--new-issue",
+            "--url",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/n8henrie/git-repo/-/issues/new\n"
+    );
+
+    let output = git_repo(
+        dir.path(),
+        &[
+            "--print",
+            "--new-issue",
+            "--url",
+            "git@git.example.com:team/repo.git",
+        ],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://git.example.com/team/repo\n"
+    );
+    assert!(String::from_utf8(output.stderr)?.contains("Unrecognized provider"));
+    Ok(())
+}
+
+#[test]
+fn test_scheme_http() -> Result<()> {
+    let dir = repo_with_remote("git@git.internal:team/repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--scheme", "http"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "http://git.internal/team/repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_list() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "mirror",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["list"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "\
+NAME    CLONE URL                             WEB URL
+mirror  git@gitlab.com:n8henrie/git-repo.git  https://gitlab.com/n8henrie/git-repo
+origin  git@github.com:n8henrie/git-repo.git  https://github.com/n8henrie/git-repo
+"
+    );
+
+    let dir = tempfile::tempdir()?;
+    git(dir.path(), &["init", "--quiet"])?;
+    let output = git_repo(dir.path(), &["list"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "NAME  CLONE URL  WEB URL\n"
+    );
+    assert!(String::from_utf8(output.stderr)?.contains("no remotes"));
+    Ok(())
+}
+
+#[test]
+fn test_keep_git_page() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--keep-git", "--branch", "main"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/tree/main\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_action_env() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let run = |action: &str, args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_git-repo"))
+            .args(args)
+            .current_dir(dir.path())
+            .env("BROWSER", "/nonexistent/browser")
+            .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+            .env("GIT_REPO_ACTION", action)
+            .output()
+    };
+
+    let output = run("print", &[])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    // A flag wins over the environment.
+    let output = run("print", &["--open"])?;
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let output = run("browse", &[])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("Invalid GIT_REPO_ACTION"));
+    Ok(())
+}
+
+#[test]
+fn test_open_remote_page_only() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--open-remote-page-only"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let output = git_repo(dir.path(), &["--open-remote-page-only", "--pulls"])?;
+    assert!(!output.status.success());
+    Ok(())
+}
+
+#[test]
+fn test_quiet() -> Result<()> {
+    let dir = repo_with_remote("git@git.example.com:team/repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--pulls"])?;
+    assert!(String::from_utf8(output.stderr)?.contains("Unrecognized provider"));
+
+    let output = git_repo(dir.path(), &["--print", "--pulls", "--quiet"])?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://git.example.com/team/repo\n"
+    );
+    assert!(output.stderr.is_empty());
+
+    let output = git_repo(dir.path(), &["--quiet", "--verbose"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_print_compare() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "-c",
+            "user.name=git-repo",
+            "-c",
+            "user.email=git-repo@example.com",
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            "init",
+        ],
+    )?;
+    git(dir.path(), &["branch", "-M", "main"])?;
+    git(dir.path(), &["branch", "feature"])?;
+    for range in ["main..feature", "main...feature"] {
+        let output = git_repo(dir.path(), &["--print", "--compare", range])?;
+        assert_eq!(
+            String::from_utf8(output.stdout)?,
+            "https://github.com/n8henrie/git-repo/compare/main...feature\n"
+        );
+    }
+
+    let output = git_repo(dir.path(), &["--print", "--compare", "main..missing"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("\"missing\""));
+    Ok(())
+}
+
+#[test]
+fn test_edit() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--edit"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.dev/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ssh_config() -> Result<()> {
+    let dir = repo_with_remote("git@work-github:n8henrie/git-repo.git")?;
+    let home = tempfile::tempdir()?;
+    std::fs::create_dir(home.path().join(".ssh"))?;
+    std::fs::write(
+        home.path().join(".ssh").join("config"),
+        "Host work-github\n    HostName github.com\n",
+    )?;
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .args(["--print", "--ssh-config"])
+        .current_dir(dir.path())
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://work-github/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_exit_codes() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8(output.stderr)?.starts_with("Error: "));
+
+    git(dir.path(), &["init", "--quiet"])?;
+    assert_eq!(git_repo(dir.path(), &["--print"])?.status.code(), Some(2));
+
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            "git@github.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "other",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print", "--remote", "o"])?;
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(git_repo(dir.path(), &["--print"])?.status.code(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn test_print_raw() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    std::fs::create_dir(dir.path().join("src"))?;
+    std::fs::write(dir.path().join("src").join("main.rs"), "fn main() {}\n")?;
+    let output = git_repo(
+        dir.path(),
+        &["--print", "--raw", "--branch", "main", "src/main.rs:1-2"],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://raw.githubusercontent.com/n8henrie/git-repo/main/src/main.rs\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "--raw", "src"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("only apply to files"));
+    Ok(())
+}
+
+#[test]
+fn test_remote_url_type() -> Result<()> {
+    let dir = repo_with_remote("https://github.com/n8henrie/git-repo")?;
+    for (url_type, expected) in [
+        ("web", "https://github.com/n8henrie/git-repo\n"),
+        ("https-clone", "https://github.com/n8henrie/git-repo.git\n"),
+        ("ssh-clone", "git@github.com:n8henrie/git-repo.git\n"),
+    ] {
+        let output = git_repo(dir.path(), &["--print", "--remote-url-type", url_type])?;
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_print_name() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "upstream",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print-name", "--remote", "upstream"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "upstream\thttps://gitlab.com/n8henrie/git-repo\n"
+    );
+
+    let output = git_repo(dir.path(), &["--print", "--print-name", "--all"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "origin\thttps://github.com/n8henrie/git-repo\n\
+         upstream\thttps://gitlab.com/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_template() -> Result<()> {
+    let dir = repo_with_remote("git@git.example.com:group/repo.git")?;
+    std::fs::write(dir.path().join("README.md"), "# repo\n")?;
+    let output = git_repo(
+        dir.path(),
+        &[
+            "--print",
+            "--branch",
+            "dev",
+            "--template",
+            "https://{host}/{path}/src/{branch}/{file}#{line}",
+            "README.md:3",
+        ],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://git.example.com/group/repo/src/dev/README.md#3\n"
+    );
+
+    let output = git_repo(
+        dir.path(),
+        &["--print", "--template", "https://{host}/{repo}"],
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("unknown placeholder {repo}"));
+
+    for flag in ["--all", "--which", "--suggest-mirrors"] {
+        let output = git_repo(dir.path(), &["--template", "https://{host}/{path}", flag])?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("cannot be used with"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_which() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "upstream",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    git(dir.path(), &["remote", "add", "local", "/srv/git/repo.git"])?;
+    let output = git_repo(dir.path(), &["--which"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Remotes: 2\nChosen: origin: git@github.com:n8henrie/git-repo.git\n"
+    );
+
+    let output = git_repo(dir.path(), &["--which", "--no-default"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "Remotes: 2\nChosen: none, you'd be prompted to choose from 2 URLs\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_settings() -> Result<()> {
+    let dir = repo_with_remote("git@gitlab.com:group/repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--settings"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://gitlab.com/group/repo/-/edit\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_default_branch_override() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    std::fs::write(dir.path().join("README.md"), "# git-repo\n")?;
+    let output = git_repo(
+        dir.path(),
+        &["--print", "--default-branch", "trunk", "README.md"],
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/blob/trunk/README.md\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_git_subcommand() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["repo", "--print"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_git-repo")).parent().unwrap();
+    let mut paths = vec![bin_dir.to_path_buf()];
+    paths.extend(std::env::split_paths(
+        &std::env::var_os("PATH").unwrap_or_default(),
+    ));
+    let output = Command::new("git")
+        .args(["repo", "--print"])
+        .current_dir(dir.path())
+        .env("PATH", std::env::join_paths(paths)?)
+        .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_open_count() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "upstream",
+            "git@gitlab.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "mirror",
+            "git@codeberg.org:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--all", "--open-count", "2"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Not opening 3 URLs"), "{}", stderr);
+    assert!(!stderr.contains("/nonexistent/browser"));
+    Ok(())
+}
+
+#[test]
+fn test_log_env() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .arg("--print")
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+        .env("GIT_REPO_LOG", "debug")
+        .output()?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("[DEBUG git_repo::remote] parsed 1 remotes"),
+        "{}",
+        stderr
+    );
+
+    let output = git_repo(dir.path(), &["--print"])?;
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
+
+#[test]
+fn test_fetch_and_push_url_only() -> Result<()> {
+    let dir = repo_with_remote("https://github.com/upstream/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "set-url",
+            "--push",
+            "origin",
+            "git@github.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    for (flag, expected) in [
+        ("--fetch-url-only", "https://github.com/upstream/git-repo\n"),
+        ("--push-url-only", "https://github.com/n8henrie/git-repo\n"),
+    ] {
+        let output = git_repo(dir.path(), &["--print", "--remote", "origin", flag])?;
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_host_map_env() -> Result<()> {
+    let dir = repo_with_remote("git@github.mycorp.com:team/repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--pulls"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.mycorp.com/team/repo\n"
+    );
+    assert!(String::from_utf8(output.stderr)?.contains("Unrecognized provider"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_git-repo"))
+        .args(["--print", "--pulls"])
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path().join(".config"))
+        .env("GIT_REPO_HOST_MAP", "github.mycorp.com=github")
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.mycorp.com/team/repo/pulls\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_conflicting_pages() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--print", "--ci"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/actions\n"
+    );
+    for args in &[
+        &["--ci", "--pulls"][..],
+        &["--ci", "--branch", "main"],
+        &["--ci", "README.md"],
+    ] {
+        let output = git_repo(dir.path(), args)?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("cannot be used with"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_show_command() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--show-command", "--pulls"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let expected = match std::env::consts::OS {
+        "linux" => "/nonexistent/browser https://github.com/n8henrie/git-repo/pulls\n",
+        "macos" => "open -a /nonexistent/browser https://github.com/n8henrie/git-repo/pulls\n",
+        _ => return Ok(()),
+    };
+    assert_eq!(String::from_utf8(output.stdout)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_gist() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    for (remote, expected) in [
+        (
+            "git@github.com:0123456789abcdef0123456789abcdef.git",
+            "https://gist.github.com/0123456789abcdef0123456789abcdef\n",
+        ),
+        (
+            "git@github.com:n8henrie/git-repo.git",
+            "https://github.com/n8henrie/git-repo\n",
+        ),
+    ] {
+        let output = git_repo(dir.path(), &["--print", "--url", remote])?;
+        assert_eq!(String::from_utf8(output.stdout)?, expected);
+    }
+
+    let dir = repo_with_remote("git@github.com:0123456789abcdef0123456789abcdef.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "upstream",
+            "git@github.com:n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--print", "--all"])?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains("https://gist.github.com/0123456789abcdef0123456789abcdef\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("https://github.com/n8henrie/git-repo\n"));
+    Ok(())
+}
+
+#[test]
+fn test_confirm_without_terminal() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    let output = git_repo(dir.path(), &["--confirm"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("Not opening https://github.com/n8henrie/git-repo without a terminal"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("/nonexistent/browser"));
+
+    let output = git_repo(dir.path(), &["--confirm", "--print"])?;
+    assert!(String::from_utf8(output.stderr)?.contains("cannot be used with"));
+    Ok(())
+}
+
+#[test]
+fn test_print_files() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    std::fs::create_dir(dir.path().join("src"))?;
+    std::fs::write(dir.path().join("src").join("main.rs"), "")?;
+    std::fs::write(dir.path().join("src").join("lib.rs"), "")?;
+    let output = git_repo(
+        &dir.path().join("src"),
+        &["--print", "main.rs:42", "lib.rs:1-3"],
+    )?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "https://github.com/n8henrie/git-repo/blob/main/src/main.rs#L42\n\
+         https://github.com/n8henrie/git-repo/blob/main/src/lib.rs#L1-L3\n"
+    );
+
+    let output = git_repo(
+        dir.path(),
+        &["--open-count", "1", "src/main.rs", "src/lib.rs"],
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Not opening 2 URLs"), "{}", stderr);
+    assert!(!stderr.contains("/nonexistent/browser"));
+    Ok(())
+}
+
+#[test]
+fn test_suggest_mirrors() -> Result<()> {
+    let dir = repo_with_remote("git@github.com:n8henrie/git-repo.git")?;
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "mirror",
+            "https://codeberg.org/n8henrie/git-repo.git",
+        ],
+    )?;
+    let output = git_repo(dir.path(), &["--suggest-mirrors"])?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "git remote add gitlab git@gitlab.com:n8henrie/git-repo.git\n\
+         git remote add bitbucket git@bitbucket.org:n8henrie/git-repo.git\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use git_repo::{format_url, urls_from_output};
+
+#[test]
+fn test_format_remotes_without_git() -> git_repo::Result<()> {
+    let output = "origin\tgit@github.com:n8henrie/git-repo.git (fetch)
+origin\tgit@github.com:n8henrie/git-repo.git (push)
+upstream\tssh://git@gitlab.com:2222/n8henrie/git-repo.git (fetch)
+upstream\tssh://git@gitlab.com:2222/n8henrie/git-repo.git (push)";
+    let urls = urls_from_output(output)
+        .iter()
+        .filter_map(|remote| remote.url().map(|url| format_url(url, true)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        urls,
+        [
+            "https://github.com/n8henrie/git-repo",
+            "https://gitlab.com/n8henrie/git-repo",
+        ]
+    );
+    Ok(())
+}